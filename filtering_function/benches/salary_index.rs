@@ -0,0 +1,39 @@
+// Players above a threshold per team: the lazy Polars filter and group-by
+// (`count_above`) against binary searches in the per-team salary index.
+// That the two agree is tested in salary_index.rs.
+//
+//   cargo bench --bench salary_index
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use polars_lambda_axum::count_above;
+use polars_lambda_axum::load_salaries;
+use polars_lambda_axum::salary_index::SalaryIndex;
+
+const THRESHOLDS: [f64; 3] = [100_000.0, 800_000.0, 5_000_000.0];
+
+fn threshold_queries(c: &mut Criterion) {
+    let df = load_salaries().unwrap();
+    let index = SalaryIndex::build(&df).unwrap();
+    let mut group = c.benchmark_group("count_above");
+    for threshold in THRESHOLDS {
+        group.bench_with_input(BenchmarkId::new("lazy", threshold), &threshold, |b, &t| {
+            b.iter(|| count_above(black_box(&df), black_box(t)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("index", threshold), &threshold, |b, &t| {
+            b.iter(|| index.count_above_frame(black_box(t)).unwrap())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("index_counts", threshold),
+            &threshold,
+            |b, &t| b.iter(|| index.count_above(black_box(t)).len()),
+        );
+    }
+    group.finish();
+
+    c.bench_function("build_index", |b| {
+        b.iter(|| SalaryIndex::build(black_box(&df)).unwrap())
+    });
+}
+
+criterion_group!(benches, threshold_queries);
+criterion_main!(benches);
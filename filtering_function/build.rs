@@ -0,0 +1,82 @@
+// Compiles mls_salaries.csv, the embedded MLS league, into an Arrow IPC file
+// in OUT_DIR that the crate includes and reads without parsing CSV.
+//
+// Every row is checked first and the build fails listing each bad row:
+// five fields, no empty field, and a finite, non-negative salary.
+
+use polars::prelude::*;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+const SOURCE: &str = "mls_salaries.csv";
+const HEADER: &str = "first_name,last_name,team,position,salary";
+
+fn problems(csv: &str) -> Vec<String> {
+    let mut lines = csv.lines().enumerate();
+    let mut problems = Vec::new();
+    match lines.next() {
+        Some((_, HEADER)) => {}
+        other => problems.push(format!(
+            "{}:1: expected the header {:?}, found {:?}",
+            SOURCE,
+            HEADER,
+            other.map_or("", |(_, line)| line)
+        )),
+    }
+    for (number, line) in lines {
+        let mut problem = |message: String| {
+            problems.push(format!(
+                "{}:{}: {} in {:?}",
+                SOURCE,
+                number + 1,
+                message,
+                line
+            ))
+        };
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() != 5 {
+            problem(format!("expected 5 fields, found {}", fields.len()));
+            continue;
+        }
+        for (name, field) in HEADER.split(',').zip(&fields) {
+            if field.trim().is_empty() {
+                problem(format!("empty {}", name));
+            }
+        }
+        match fields[4].parse::<f64>() {
+            Ok(salary) if salary.is_finite() && salary >= 0.0 => {}
+            _ => problem(format!("invalid salary {:?}", fields[4])),
+        }
+    }
+    problems
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", SOURCE);
+    let csv = std::fs::read_to_string(SOURCE).expect("cannot read mls_salaries.csv");
+    let csv = csv.trim_start_matches('\u{feff}');
+
+    let problems = problems(csv);
+    if !problems.is_empty() {
+        panic!("{} bad rows:\n{}", problems.len(), problems.join("\n"));
+    }
+
+    let schema = Schema::from_iter([
+        Field::new("first_name", DataType::Utf8),
+        Field::new("last_name", DataType::Utf8),
+        Field::new("team", DataType::Utf8),
+        Field::new("position", DataType::Utf8),
+        Field::new("salary", DataType::Float64),
+    ]);
+    let mut df = CsvReader::new(Cursor::new(csv))
+        .has_header(true)
+        .with_schema(Arc::new(schema))
+        .finish()
+        .expect("cannot parse mls_salaries.csv");
+
+    let out = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("mls_salaries.arrow");
+    let mut file = std::fs::File::create(&out).expect("cannot create the Arrow file");
+    IpcWriter::new(&mut file)
+        .finish(&mut df)
+        .expect("cannot write the Arrow file");
+}
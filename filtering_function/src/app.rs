@@ -0,0 +1,1388 @@
+// The HTTP API: every route, its handlers and the middleware around them.
+//
+// `app` builds the router served by the Lambda (see main.rs); tests drive it
+// directly as a tower service.
+
+use crate::auth::{KeyStore, Rejection, Scope};
+use crate::bands::{band_matrix, Band, BandMatrix, BandSchema, TeamBands};
+use crate::caching::{conditional, CachePolicy};
+use crate::charts::{bar_chart, box_plot, histogram, labelled_values, Chart, ChartOptions, Sort};
+use crate::currency::{Format, Locale, Money, RateTable};
+use crate::efficiency::{cost_efficiency, read_stats, read_stats_file, DEFAULT_MIN_SIMILARITY};
+use crate::export::{salary_workbook, CONTENT_TYPE};
+use crate::filter::check_filter;
+use crate::graphql::{schema, SalariesSchema};
+use crate::inequality::{team_inequality, LorenzPoint, TeamInequality};
+use crate::inverse::{nth_highest_per_team, threshold_for, TeamCutoff};
+use crate::leagues::{league, League, DEFAULT_LEAGUE, LEAGUES};
+use crate::metrics::Recorder;
+use crate::outliers::{outliers, Baseline, Method, Outlier};
+use crate::report::{index_page, team_page, DEFAULT_THRESHOLD};
+use crate::results::{cached, results, Diagnostics, Limits, Stats};
+use crate::roster::{build_roster, Roster, RosterPick, RosterRequest, RosterResult};
+use crate::rules::{compliance, Category, ClassifiedPlayer, RuleSet, TeamCompliance};
+use crate::salaries_by;
+use crate::simulate::{simulate, Aggregation, Edit, PlayerRef, Simulation, SimulationResult};
+use crate::telemetry::{dataset_version, phase, phase_async, Phase};
+use crate::top_n::{top_n, Grouping, Ties};
+use crate::{players, Player};
+use async_graphql::http::GraphiQLSource;
+use axum::{
+    async_trait,
+    body::{boxed, Full, HttpBody},
+    extract::{FromRequestParts, Path, Query, State},
+    http::{header, request::Parts, Request, StatusCode},
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Json, Response},
+    routing::{get, post, MethodRouter},
+    BoxError, Extension, Router,
+};
+use polars::prelude::{DataFrame, IntoLazy, PolarsError};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::openapi::path::{Parameter, ParameterBuilder, ParameterIn};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::openapi::{ObjectBuilder, Required, SchemaType};
+use utoipa::{IntoParams, Modify, OpenApi, ToSchema};
+
+fn error(status: StatusCode, message: impl ToString) -> (StatusCode, Json<Value>) {
+    (status, Json(json!({ "error": message.to_string() })))
+}
+
+// A failure computing an answer from the league's data, e.g. a CSV file
+// with unexpected values.
+fn failed(err: PolarsError) -> (StatusCode, Json<Value>) {
+    error(StatusCode::INTERNAL_SERVER_ERROR, err)
+}
+
+// The body of every error response, see `error`.
+#[derive(ToSchema)]
+#[schema(as = Error)]
+#[allow(dead_code)]
+struct ErrorBody {
+    error: String,
+}
+
+// A DataFrame rendered as text.
+#[derive(Serialize, ToSchema)]
+struct Payload {
+    payload: String,
+}
+
+// The league named by the `:league` path segment, or DEFAULT_LEAGUE on the
+// un-prefixed routes.
+async fn requested_league<S: Send + Sync>(
+    parts: &mut Parts,
+    state: &S,
+) -> Result<&'static League, (StatusCode, Json<Value>)> {
+    let params = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+        .await
+        .map(|Path(params)| params)
+        .unwrap_or_default();
+    let id = params.get("league").map_or(DEFAULT_LEAGUE, String::as_str);
+    league(id).ok_or_else(|| error(StatusCode::NOT_FOUND, format!("unknown league {}", id)))
+}
+
+// The requested league itself, see `requested_league`.
+struct RequestedLeague(&'static League);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RequestedLeague {
+    type Rejection = (StatusCode, Json<Value>);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        requested_league(parts, state).await.map(RequestedLeague)
+    }
+}
+
+// The salaries of the requested league.
+struct Salaries(DataFrame);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Salaries {
+    type Rejection = (StatusCode, Json<Value>);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let league = requested_league(parts, state).await?;
+        let (df, version) = phase(Phase::Load, || Ok((league.frame()?, league.version()?)))
+            .map_err(|err: PolarsError| error(StatusCode::SERVICE_UNAVAILABLE, err))?;
+        dataset_version(&version);
+        if let Some(recorder) = parts.extensions.get::<Recorder>() {
+            recorder.rows_loaded(df.height());
+        }
+        Ok(Salaries(df))
+    }
+}
+
+// Cached results computed from the requested league's salaries, see
+// results.rs.
+struct Results {
+    league: &'static League,
+    version: String,
+    recorder: Option<Recorder>,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Results {
+    type Rejection = (StatusCode, Json<Value>);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let league = requested_league(parts, state).await?;
+        let version = phase(Phase::Load, || league.version())
+            .map_err(|err| error(StatusCode::SERVICE_UNAVAILABLE, err))?;
+        dataset_version(&version);
+        Ok(Results {
+            league,
+            version,
+            recorder: parts.extensions.get::<Recorder>().cloned(),
+        })
+    }
+}
+
+impl Results {
+    // The result of `query`, a normalized description of the computation,
+    // from the cache or `compute`.
+    fn get_or_compute(
+        &self,
+        query: &str,
+        compute: impl FnOnce() -> Result<DataFrame, PolarsError>,
+    ) -> Result<DataFrame, PolarsError> {
+        let (df, hit) = cached(self.league.id, &self.version, query, compute)?;
+        if let Some(recorder) = &self.recorder {
+            recorder.cache(hit);
+        }
+        Ok(df)
+    }
+
+    // Counts the league's rows as loaded, for computations that read them
+    // without the `Salaries` extractor.
+    fn rows_loaded(&self) -> Result<(), PolarsError> {
+        if let Some(recorder) = &self.recorder {
+            recorder.rows_loaded(self.league.frame()?.height());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct MoneyParams {
+    /// ISO 4217 code to convert salary figures in the response to, e.g. EUR.
+    /// Amounts in the request (thresholds, filter values, budgets, band
+    /// edges) are always in the league's own currency.
+    currency: Option<String>,
+    /// Render amounts as text: compact ($1.25M) or full ($1,250,000.00)
+    format: Option<Format>,
+    /// Separators for formatted amounts: en-US (default), de-DE or fr-FR
+    #[serde(default)]
+    locale: Locale,
+}
+
+// Applies the `currency`, `format` and `locale` query parameters to the
+// money fields of a JSON response. Handlers that render a DataFrame as text,
+// a workbook or a chart read the `Money` extension and localize their data
+// themselves. Only answers are converted: amounts in the path, query or body
+// stay in the league's currency, so the same request selects the same
+// players whatever `currency` it asks for.
+async fn localize<B>(
+    State(rates): State<Arc<RateTable>>,
+    Query(params): Query<MoneyParams>,
+    path: Option<Path<HashMap<String, String>>>,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if params.currency.is_none() && params.format.is_none() {
+        return next.run(request).await;
+    }
+    let id = path
+        .as_ref()
+        .and_then(|Path(params)| params.get("league"))
+        .map_or(DEFAULT_LEAGUE, String::as_str);
+    let Some(league) = league(id) else {
+        return next.run(request).await;
+    };
+    let money = match Money::new(
+        &rates,
+        league.currency,
+        params.currency.as_deref(),
+        params.format,
+        params.locale,
+    ) {
+        Ok(money) => money,
+        Err(err) => return error(StatusCode::BAD_REQUEST, err).into_response(),
+    };
+
+    request.extensions_mut().insert(money.clone());
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|content_type| content_type.as_bytes().starts_with(b"application/json"));
+    if !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match hyper::body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(err) => return error(StatusCode::INTERNAL_SERVER_ERROR, err).into_response(),
+    };
+    let Ok(mut value) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, boxed(Full::from(bytes)));
+    };
+    money.localize_json(&mut value);
+    parts.headers.remove(header::CONTENT_LENGTH);
+    let body = serde_json::to_vec(&value).unwrap();
+    Response::from_parts(parts, boxed(Full::from(body)))
+}
+
+// Requests anyone may make: the docs, which load before a key can be
+// entered. Everything else, the browser pages included, needs a key.
+fn public<B>(request: &Request<B>) -> bool {
+    ["/docs", "/openapi.json"].contains(&request.uri().path())
+}
+
+// The API key from `X-API-Key` or an `Authorization: Bearer` header.
+fn api_key<B>(request: &Request<B>) -> Option<&str> {
+    let headers = request.headers();
+    if let Some(key) = headers.get("x-api-key") {
+        return key.to_str().ok();
+    }
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+// Admits requests with a key of the route's scope and within its limits;
+// see auth.rs.
+async fn authenticate<B>(
+    State(keys): State<Arc<KeyStore>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if public(&request) {
+        return next.run(request).await;
+    }
+    let path = request.uri().path();
+    let retry = |status, message: &str, retry_after: Duration| {
+        let mut response = error(status, message).into_response();
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, retry_after.as_secs().into());
+        response
+    };
+    let admission = match keys.admit(api_key(&request), Scope::required(path)) {
+        Ok(admission) => admission,
+        Err(Rejection::MissingKey) => {
+            return error(StatusCode::UNAUTHORIZED, "missing API key").into_response()
+        }
+        Err(Rejection::UnknownKey) => {
+            return error(StatusCode::UNAUTHORIZED, "invalid API key").into_response()
+        }
+        Err(Rejection::Forbidden { name, required }) => {
+            tracing::warn!(key = name, ?required, path, "API key lacks scope");
+            return error(
+                StatusCode::FORBIDDEN,
+                format!("this API key cannot call {}", path),
+            )
+            .into_response();
+        }
+        Err(Rejection::RateLimited { retry_after }) => {
+            return retry(
+                StatusCode::TOO_MANY_REQUESTS,
+                "rate limit exceeded",
+                retry_after,
+            )
+        }
+        Err(Rejection::QuotaExceeded { retry_after }) => {
+            return retry(
+                StatusCode::TOO_MANY_REQUESTS,
+                "daily quota exceeded",
+                retry_after,
+            )
+        }
+    };
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert("x-ratelimit-remaining", admission.remaining.into());
+    if let Some(quota) = admission.quota_remaining {
+        headers.insert("x-quota-remaining", quota.into());
+    }
+    response
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ReportParams {
+    /// Salary to count players above, 1000000 by default, in the league's
+    /// currency
+    threshold: Option<f64>,
+}
+
+// HTML report of the default league for use from a browser.
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "report",
+    params(ReportParams),
+    responses((status = 200, description = "Team counts above the threshold", content_type = "text/html", body = String))
+)]
+async fn root(
+    Salaries(df): Salaries,
+    Query(params): Query<ReportParams>,
+) -> Result<Html<String>, (StatusCode, Json<Value>)> {
+    let threshold = params.threshold.unwrap_or(DEFAULT_THRESHOLD);
+    index_page(&df, threshold)
+        .map(Html)
+        .map_err(|err| error(StatusCode::INTERNAL_SERVER_ERROR, err))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+struct TeamPath {
+    team: String,
+}
+
+//simple url: /teams/Toronto%20FC?threshold=500000
+#[utoipa::path(
+    get,
+    path = "/teams/{team}",
+    tag = "report",
+    params(TeamPath, ReportParams),
+    responses(
+        (status = 200, description = "The team's roster by salary", content_type = "text/html", body = String),
+        (status = 404, description = "Unknown team", body = Error)
+    )
+)]
+async fn get_team_page(
+    Salaries(df): Salaries,
+    Path(TeamPath { team }): Path<TeamPath>,
+    Query(params): Query<ReportParams>,
+) -> Result<Html<String>, (StatusCode, Json<Value>)> {
+    let threshold = params.threshold.unwrap_or(DEFAULT_THRESHOLD);
+    match team_page(&df, &team, threshold) {
+        Ok(Some(page)) => Ok(Html(page)),
+        Ok(None) => Err(error(
+            StatusCode::NOT_FOUND,
+            format!("unknown team {}", team),
+        )),
+        Err(err) => Err(error(StatusCode::INTERNAL_SERVER_ERROR, err)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/leagues",
+    tag = "leagues",
+    responses((status = 200, body = [League]))
+)]
+async fn get_leagues() -> Json<&'static [League]> {
+    Json(LEAGUES)
+}
+
+#[utoipa::path(
+    get,
+    path = "/leagues/{league}",
+    tag = "leagues",
+    params(("league" = String, Path, description = "League id, e.g. mls")),
+    responses(
+        (status = 200, body = League),
+        (status = 404, description = "Unknown league", body = Error)
+    )
+)]
+async fn get_league(
+    Path(id): Path<String>,
+) -> Result<Json<&'static League>, (StatusCode, Json<Value>)> {
+    league(&id)
+        .map(Json)
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, format!("unknown league {}", id)))
+}
+
+#[derive(Serialize, ToSchema)]
+struct Reloaded {
+    league: &'static str,
+    players: usize,
+}
+
+// Re-reads a league's salaries, e.g. after its CSV file was replaced.
+#[utoipa::path(
+    post,
+    path = "/admin/leagues/{league}/reload",
+    tag = "admin",
+    params(("league" = String, Path, description = "League id, e.g. mls")),
+    responses(
+        (status = 200, body = Reloaded),
+        (status = 404, description = "Unknown league", body = Error),
+        (status = 503, description = "The league's data cannot be loaded; the previous data is still served", body = Error)
+    )
+)]
+async fn post_reload(Path(id): Path<String>) -> Result<Json<Reloaded>, (StatusCode, Json<Value>)> {
+    let league = league(&id)
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, format!("unknown league {}", id)))?;
+    let df = league
+        .reload()
+        .map_err(|err| error(StatusCode::SERVICE_UNAVAILABLE, err))?;
+    tracing::info!(league = league.id, players = df.height(), "reloaded league");
+    Ok(Json(Reloaded {
+        league: league.id,
+        players: df.height(),
+    }))
+}
+
+//simple url: /diagnostics/cache
+#[utoipa::path(
+    get,
+    path = "/diagnostics/cache",
+    tag = "diagnostics",
+    responses((status = 200, description = "Result cache limits and hit/miss statistics", body = Diagnostics))
+)]
+async fn get_cache_diagnostics() -> Json<Diagnostics> {
+    Json(results().lock().unwrap().diagnostics())
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+struct FilterPath {
+    /// Salary to count players above, in the league's currency
+    value: f64,
+}
+
+//simple url: /salaries/filter/5 or /leagues/mls/salaries/filter/5
+#[utoipa::path(
+    get,
+    path = "/salaries/filter/{value}",
+    tag = "salaries",
+    params(FilterPath),
+    responses((status = 200, description = "Players above the salary per team", body = Payload))
+)]
+async fn get_filter(
+    results: Results,
+    Path(FilterPath { value }): Path<FilterPath>,
+) -> Result<Json<Payload>, (StatusCode, Json<Value>)> {
+    let df = phase(Phase::Aggregate, || {
+        results.get_or_compute(&format!("count_above?value={}", value), || {
+            results.rows_loaded()?;
+            results.league.count_above(value)
+        })
+    })
+    .map_err(failed)?;
+    Ok(Json(Payload {
+        payload: phase(Phase::Serialize, || format!("{}", df)),
+    }))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct PlayersParams {
+    /// Filter expression, e.g. `team == "LAFC" and salary > 500000`
+    filter: String,
+}
+
+// A filter that failed to parse or type check.
+#[derive(Serialize, ToSchema)]
+struct FilterErrorBody {
+    error: String,
+    // Byte range of the offending token in `filter`.
+    start: usize,
+    end: usize,
+    // The message with the filter and a caret line under the token.
+    detail: String,
+}
+
+//simple url: /salaries/players?filter=position in ["F","M-F"] and salary >= 1000000
+#[utoipa::path(
+    get,
+    path = "/salaries/players",
+    tag = "salaries",
+    params(PlayersParams, MoneyParams),
+    responses(
+        (status = 200, description = "Players matching the filter", body = [Player]),
+        (status = 400, description = "The filter does not parse or type check", body = FilterErrorBody)
+    )
+)]
+async fn get_players(
+    Salaries(df): Salaries,
+    results: Results,
+    Query(PlayersParams { filter }): Query<PlayersParams>,
+) -> Result<Json<Vec<Player>>, Response> {
+    let checked = check_filter(&filter, &df.schema()).map_err(|err| {
+        let body = FilterErrorBody {
+            error: err.message.clone(),
+            start: err.start,
+            end: err.end,
+            detail: err.render(&filter),
+        };
+        (StatusCode::BAD_REQUEST, Json(body)).into_response()
+    })?;
+    let query = format!("filter?expr={}", checked.key());
+    phase(Phase::Filter, || {
+        results.get_or_compute(&query, || df.lazy().filter(checked.to_expr()).collect())
+    })
+    .and_then(|df| phase(Phase::Serialize, || players(&df)))
+    .map(Json)
+    .map_err(|err| error(StatusCode::INTERNAL_SERVER_ERROR, err).into_response())
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+struct TopNPath {
+    grouping: Grouping,
+    /// Players per group
+    n: u32,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct TopNParams {
+    #[serde(default)]
+    ties: Ties,
+}
+
+//simple url: /salaries/top/team/3?ties=dense
+#[utoipa::path(
+    get,
+    path = "/salaries/top/{grouping}/{n}",
+    tag = "salaries",
+    params(TopNPath, TopNParams, MoneyParams),
+    responses((status = 200, description = "The best paid players per group", body = Payload))
+)]
+async fn get_top_n(
+    Salaries(df): Salaries,
+    results: Results,
+    Path(TopNPath { grouping, n }): Path<TopNPath>,
+    Query(params): Query<TopNParams>,
+    money: Option<Extension<Money>>,
+) -> Result<Json<Payload>, (StatusCode, Json<Value>)> {
+    let query = format!(
+        "top_n?grouping={:?}&n={}&ties={:?}",
+        grouping, n, params.ties
+    );
+    let mut df = phase(Phase::Aggregate, || {
+        results.get_or_compute(&query, || top_n(&df, n, grouping, params.ties))
+    })
+    .map_err(failed)?;
+    if let Some(Extension(money)) = money {
+        df = money.localize_frame(&df).map_err(failed)?;
+    }
+    Ok(Json(Payload {
+        payload: phase(Phase::Serialize, || format!("{}", df)),
+    }))
+}
+
+//simple url: /salaries/inequality
+#[utoipa::path(
+    get,
+    path = "/salaries/inequality",
+    tag = "salaries",
+    params(MoneyParams),
+    responses((status = 200, body = [TeamInequality]))
+)]
+async fn get_inequality(
+    Salaries(df): Salaries,
+) -> Result<Json<Vec<TeamInequality>>, (StatusCode, Json<Value>)> {
+    phase(Phase::Aggregate, || team_inequality(&df))
+        .map(Json)
+        .map_err(failed)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+struct GroupingPath {
+    grouping: Grouping,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct OutlierParams {
+    #[serde(default)]
+    method: Method,
+    /// Score above which a salary is an outlier, by default 3 (z-score), 3.5
+    /// (MAD) or 1.5 (IQR)
+    threshold: Option<f64>,
+}
+
+//simple url: /salaries/outliers/position?method=iqr&threshold=3
+#[utoipa::path(
+    get,
+    path = "/salaries/outliers/{grouping}",
+    tag = "salaries",
+    params(GroupingPath, OutlierParams, MoneyParams),
+    responses((status = 200, body = [Outlier]))
+)]
+async fn get_outliers(
+    Salaries(df): Salaries,
+    Path(GroupingPath { grouping }): Path<GroupingPath>,
+    Query(params): Query<OutlierParams>,
+) -> Result<Json<Vec<Outlier>>, (StatusCode, Json<Value>)> {
+    phase(Phase::Aggregate, || {
+        outliers(&df, grouping, params.method, params.threshold)
+    })
+    .map(Json)
+    .map_err(failed)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+struct NthPath {
+    /// Rank of the salary within each team, 1 for the highest
+    n: usize,
+}
+
+//simple url: /salaries/nth/3
+#[utoipa::path(
+    get,
+    path = "/salaries/nth/{n}",
+    tag = "salaries",
+    params(NthPath, MoneyParams),
+    responses((status = 200, body = [TeamCutoff]))
+)]
+async fn get_nth_highest(
+    Salaries(df): Salaries,
+    Path(NthPath { n }): Path<NthPath>,
+) -> Result<Json<Vec<TeamCutoff>>, (StatusCode, Json<Value>)> {
+    phase(Phase::Aggregate, || nth_highest_per_team(&df, n))
+        .map(Json)
+        .map_err(failed)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+struct ThresholdPath {
+    /// Number of players that should earn more than the threshold
+    players: usize,
+}
+
+#[derive(Serialize, ToSchema)]
+struct ThresholdAnswer {
+    players: usize,
+    // The lowest salary leaving exactly `players` above it. None when the
+    // league has no more players than that, or when a tie at the cut-off
+    // makes exactly that many impossible.
+    threshold: Option<f64>,
+}
+
+//simple url: /salaries/threshold/50
+#[utoipa::path(
+    get,
+    path = "/salaries/threshold/{players}",
+    tag = "salaries",
+    params(ThresholdPath, MoneyParams),
+    responses((status = 200, body = ThresholdAnswer))
+)]
+async fn get_threshold(
+    Salaries(df): Salaries,
+    Path(ThresholdPath { players }): Path<ThresholdPath>,
+) -> Result<Json<ThresholdAnswer>, (StatusCode, Json<Value>)> {
+    let threshold = phase(Phase::Aggregate, || threshold_for(&df, players)).map_err(failed)?;
+    Ok(Json(ThresholdAnswer { players, threshold }))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct BandParams {
+    /// "standard" (default) or "mls"
+    preset: Option<String>,
+    /// Comma separated, finite and strictly ascending edges, e.g.
+    /// 100000,500000,1000000
+    edges: Option<String>,
+}
+
+//simple url: /salaries/bands?edges=100000,500000 or /salaries/bands?preset=mls
+#[utoipa::path(
+    get,
+    path = "/salaries/bands",
+    tag = "salaries",
+    params(BandParams, MoneyParams),
+    responses(
+        (status = 200, body = BandMatrix),
+        (status = 400, description = "Invalid edges or unknown preset", body = Error)
+    )
+)]
+async fn get_bands(
+    Salaries(df): Salaries,
+    Query(params): Query<BandParams>,
+) -> Result<Json<BandMatrix>, (StatusCode, Json<Value>)> {
+    let bad_request = |err: PolarsError| error(StatusCode::BAD_REQUEST, err);
+    let schema = match (params.edges, params.preset.as_deref()) {
+        (Some(edges), _) => {
+            let edges = edges
+                .split(',')
+                .map(|edge| edge.trim().parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| error(StatusCode::BAD_REQUEST, format!("invalid edge: {}", err)))?;
+            BandSchema::from_edges(&edges).map_err(bad_request)?
+        }
+        (None, Some("mls")) => BandSchema::mls(),
+        (None, None | Some("standard")) => BandSchema::standard(),
+        (None, Some(preset)) => {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                format!("unknown preset {:?}, use standard or mls", preset),
+            ))
+        }
+    };
+    phase(Phase::Aggregate, || band_matrix(&df, &schema))
+        .map(Json)
+        .map_err(failed)
+}
+
+//simple url: /salaries/rules?max_dps=3&salary_budget=5210000
+#[utoipa::path(
+    get,
+    path = "/salaries/rules",
+    tag = "salaries",
+    params(RuleSet, MoneyParams),
+    responses((status = 200, body = [TeamCompliance]))
+)]
+async fn get_rules(
+    Salaries(df): Salaries,
+    Query(rules): Query<RuleSet>,
+) -> Result<Json<Vec<TeamCompliance>>, (StatusCode, Json<Value>)> {
+    phase(Phase::Aggregate, || compliance(&df, &rules))
+        .map(Json)
+        .map_err(failed)
+}
+
+// Builds the best roster for a budget and position quotas, see `roster`.
+#[utoipa::path(
+    post,
+    path = "/salaries/roster",
+    tag = "salaries",
+    params(MoneyParams),
+    request_body = RosterRequest,
+    responses(
+        (status = 200, body = RosterResult),
+        (status = 400, description = "The quotas, alternatives or budget are out of bounds", body = Error)
+    )
+)]
+async fn post_roster(
+    Salaries(df): Salaries,
+    Json(request): Json<RosterRequest>,
+) -> Result<Json<RosterResult>, (StatusCode, Json<Value>)> {
+    phase(Phase::Aggregate, || build_roster(&df, &request))
+        .map(Json)
+        .map_err(|err| error(StatusCode::BAD_REQUEST, err))
+}
+
+// Applies hypothetical roster moves to a copy of the data, see `simulate`.
+#[utoipa::path(
+    post,
+    path = "/salaries/simulate",
+    tag = "salaries",
+    params(MoneyParams),
+    request_body = Simulation,
+    responses(
+        (status = 200, body = SimulationResult),
+        (status = 400, description = "An edit names no or several players", body = Error)
+    )
+)]
+async fn post_simulate(
+    Salaries(df): Salaries,
+    Json(simulation): Json<Simulation>,
+) -> Result<Json<SimulationResult>, (StatusCode, Json<Value>)> {
+    phase(Phase::Aggregate, || simulate(&df, &simulation))
+        .map(Json)
+        .map_err(|err| error(StatusCode::BAD_REQUEST, err))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+struct ChartPath {
+    chart: Chart,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ChartSource {
+    /// Bar chart: players above this salary per team, in the league's
+    /// currency
+    threshold: Option<f64>,
+    /// Box plot: salary distribution per team (default), position or league
+    grouping: Option<Grouping>,
+    /// POSTed aggregations: the field labelling each value, `team` by default
+    label: Option<String>,
+    /// POSTed aggregations: the field to chart, `salary` by default
+    value: Option<String>,
+}
+
+fn svg(svg: String) -> Response {
+    ([(header::CONTENT_TYPE, "image/svg+xml")], svg).into_response()
+}
+
+//simple url: /salaries/charts/bar?threshold=1000000&width=800&sort=label
+//            /salaries/charts/box?grouping=position
+//            /salaries/charts/histogram?bins=30
+#[utoipa::path(
+    get,
+    path = "/salaries/charts/{chart}",
+    tag = "charts",
+    params(ChartPath, ChartOptions, ChartSource, MoneyParams),
+    responses((status = 200, content_type = "image/svg+xml", body = String))
+)]
+async fn get_chart(
+    Salaries(df): Salaries,
+    Path(ChartPath { chart }): Path<ChartPath>,
+    Query(options): Query<ChartOptions>,
+    Query(source): Query<ChartSource>,
+    money: Option<Extension<Money>>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let failed = |err: PolarsError| error(StatusCode::INTERNAL_SERVER_ERROR, err);
+    let mut threshold = source.threshold.unwrap_or(DEFAULT_THRESHOLD);
+    // Charts plot numbers, so amounts are converted but never formatted.
+    let df = match money {
+        Some(Extension(money)) => {
+            threshold = money.convert(threshold);
+            let money = Money {
+                format: None,
+                ..money
+            };
+            money.localize_frame(&df).map_err(failed)?
+        }
+        None => df,
+    };
+    let chart = match chart {
+        Chart::Bar => {
+            let counts = phase(Phase::Aggregate, || {
+                Aggregation::CountAbove { threshold }.evaluate(&df)
+            })
+            .map_err(failed)?;
+            phase(Phase::Serialize, || {
+                bar_chart(&labelled_values(&counts, "team", "players"), &options)
+            })
+        }
+        Chart::Box => {
+            let grouping = source.grouping.unwrap_or(Grouping::Team);
+            let groups = phase(Phase::Aggregate, || {
+                salaries_by(&df, grouping.column().unwrap_or("league"))
+            })
+            .map_err(failed)?;
+            phase(Phase::Serialize, || {
+                box_plot(&groups.into_iter().collect::<Vec<_>>(), &options)
+            })
+        }
+        Chart::Histogram => {
+            let salaries = phase(Phase::Aggregate, || salaries_by(&df, "team")).map_err(failed)?;
+            phase(Phase::Serialize, || {
+                histogram(
+                    &salaries.into_values().flatten().collect::<Vec<_>>(),
+                    &options,
+                )
+            })
+        }
+    };
+    Ok(svg(chart))
+}
+
+// Charts any aggregation from `simulate`, e.g. a POST to
+// /salaries/charts/bar?label=team&value=gini with {"kind": "inequality"}.
+#[utoipa::path(
+    post,
+    path = "/salaries/charts/{chart}",
+    tag = "charts",
+    params(ChartPath, ChartOptions, ChartSource, MoneyParams),
+    request_body = Aggregation,
+    responses(
+        (status = 200, content_type = "image/svg+xml", body = String),
+        (status = 400, description = "The aggregation failed", body = Error)
+    )
+)]
+async fn post_chart(
+    Salaries(df): Salaries,
+    Path(ChartPath { chart }): Path<ChartPath>,
+    Query(options): Query<ChartOptions>,
+    Query(source): Query<ChartSource>,
+    money: Option<Extension<Money>>,
+    Json(aggregation): Json<Aggregation>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let mut result = phase(Phase::Aggregate, || aggregation.evaluate(&df))
+        .map_err(|err| error(StatusCode::BAD_REQUEST, err))?;
+    if let Some(Extension(money)) = money {
+        let money = Money {
+            format: None,
+            ..money
+        };
+        money.localize_json(&mut result);
+    }
+    let label = source.label.as_deref().unwrap_or("team");
+    let pairs = labelled_values(&result, label, source.value.as_deref().unwrap_or("salary"));
+    let chart = phase(Phase::Serialize, || match chart {
+        Chart::Bar => bar_chart(&pairs, &options),
+        Chart::Box => {
+            let mut groups: Vec<(String, Vec<f64>)> = Vec::new();
+            for (label, value) in pairs {
+                match groups.iter_mut().find(|(group, _)| *group == label) {
+                    Some((_, values)) => values.push(value),
+                    None => groups.push((label, vec![value])),
+                }
+            }
+            box_plot(&groups, &options)
+        }
+        Chart::Histogram => {
+            let values: Vec<f64> = pairs.into_iter().map(|(_, value)| value).collect();
+            histogram(&values, &options)
+        }
+    });
+    Ok(svg(chart))
+}
+
+// Excel workbook of the players, counts above `threshold`, payroll summary
+// and payroll by position. With `currency`, amounts are converted first.
+//simple url: /salaries/export.xlsx?threshold=500000&currency=EUR
+#[utoipa::path(
+    get,
+    path = "/salaries/export.xlsx",
+    tag = "salaries",
+    params(ReportParams, MoneyParams),
+    responses((
+        status = 200,
+        content_type = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        body = Vec<u8>
+    ))
+)]
+async fn get_export(
+    RequestedLeague(league): RequestedLeague,
+    Salaries(df): Salaries,
+    Query(params): Query<ReportParams>,
+    money: Option<Extension<Money>>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let threshold = params.threshold.unwrap_or(DEFAULT_THRESHOLD);
+    let workbook = phase(Phase::Serialize, || match money {
+        // Keep amounts numeric: the workbook applies its own number format.
+        Some(Extension(money)) => {
+            let money = Money {
+                format: None,
+                ..money
+            };
+            money
+                .localize_frame(&df)
+                .and_then(|df| salary_workbook(&df, money.convert(threshold), &money.currency))
+        }
+        None => salary_workbook(&df, threshold, league.currency),
+    })
+    .map_err(|err| error(StatusCode::INTERNAL_SERVER_ERROR, err))?;
+    let disposition = format!("attachment; filename=\"{}-salaries.xlsx\"", league.id);
+    Ok((
+        [
+            (header::CONTENT_TYPE, CONTENT_TYPE.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        workbook,
+    )
+        .into_response())
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct EfficiencyParams {
+    /// Lowest name similarity (0 to 1) accepted as a match, 0.85 by default
+    min_similarity: Option<f64>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct EfficiencyPayload {
+    payload: String,
+    // Stats rows that matched no player.
+    unmatched: Vec<String>,
+}
+
+fn efficiency_response(
+    salaries: &DataFrame,
+    stats: Result<DataFrame, PolarsError>,
+    params: EfficiencyParams,
+    money: Option<Extension<Money>>,
+) -> Result<Json<EfficiencyPayload>, (StatusCode, Json<Value>)> {
+    let min_similarity = params.min_similarity.unwrap_or(DEFAULT_MIN_SIMILARITY);
+    stats
+        .and_then(|stats| {
+            phase(Phase::Aggregate, || {
+                cost_efficiency(salaries, &stats, min_similarity)
+            })
+        })
+        .and_then(|efficiency| {
+            let table = match money {
+                Some(Extension(money)) => money.localize_frame(&efficiency.table)?,
+                None => efficiency.table,
+            };
+            Ok(Json(EfficiencyPayload {
+                payload: phase(Phase::Serialize, || format!("{}", table)),
+                unmatched: efficiency.unmatched,
+            }))
+        })
+        .map_err(|err| error(StatusCode::BAD_REQUEST, err))
+}
+
+// Upload a stats CSV (first_name,last_name[,team],minutes,goals,assists) as
+// the request body.
+#[utoipa::path(
+    post,
+    path = "/salaries/efficiency",
+    tag = "salaries",
+    params(EfficiencyParams, MoneyParams),
+    request_body(content = String, content_type = "text/csv"),
+    responses(
+        (status = 200, body = EfficiencyPayload),
+        (status = 400, description = "Invalid stats CSV", body = Error)
+    )
+)]
+async fn post_efficiency(
+    Salaries(df): Salaries,
+    Query(params): Query<EfficiencyParams>,
+    money: Option<Extension<Money>>,
+    csv: String,
+) -> Result<Json<EfficiencyPayload>, (StatusCode, Json<Value>)> {
+    efficiency_response(&df, read_stats(&csv), params, money)
+}
+
+// Uses the stats CSV at the path in the PLAYER_STATS_CSV environment variable.
+#[utoipa::path(
+    get,
+    path = "/salaries/efficiency",
+    tag = "salaries",
+    params(EfficiencyParams, MoneyParams),
+    responses(
+        (status = 200, body = EfficiencyPayload),
+        (status = 404, description = "PLAYER_STATS_CSV is not set", body = Error)
+    )
+)]
+async fn get_efficiency(
+    Salaries(df): Salaries,
+    Query(params): Query<EfficiencyParams>,
+    money: Option<Extension<Money>>,
+) -> Result<Json<EfficiencyPayload>, (StatusCode, Json<Value>)> {
+    let Ok(path) = std::env::var("PLAYER_STATS_CSV") else {
+        return Err(error(StatusCode::NOT_FOUND, "PLAYER_STATS_CSV is not set"));
+    };
+    efficiency_response(&df, read_stats_file(path), params, money)
+}
+
+// GraphiQL, for trying queries from a browser.
+#[utoipa::path(
+    get,
+    path = "/graphql",
+    tag = "graphql",
+    responses((status = 200, description = "GraphiQL", content_type = "text/html", body = String))
+)]
+async fn get_graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+// Players, teams and salary stats in one query, see `graphql`.
+#[utoipa::path(
+    post,
+    path = "/graphql",
+    tag = "graphql",
+    request_body(content = Object, description = "`query`, and optionally `variables` and `operationName`"),
+    responses((status = 200, description = "`data` and any `errors`", body = Object))
+)]
+async fn post_graphql(
+    Extension(schema): Extension<SalariesSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(phase_async(Phase::Aggregate, schema.execute(request)).await)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "MLS salaries API"),
+    modifiers(&ApiKeyScheme),
+    security(("api_key" = [])),
+    paths(
+        root,
+        get_team_page,
+        get_leagues,
+        get_league,
+        post_reload,
+        get_cache_diagnostics,
+        get_filter,
+        get_players,
+        get_top_n,
+        get_inequality,
+        get_outliers,
+        get_nth_highest,
+        get_threshold,
+        get_bands,
+        get_rules,
+        post_simulate,
+        post_roster,
+        get_efficiency,
+        post_efficiency,
+        get_chart,
+        post_chart,
+        get_export,
+        get_graphiql,
+        post_graphql,
+        get_openapi,
+        get_docs,
+    ),
+    components(schemas(
+        ErrorBody,
+        Reloaded,
+        Diagnostics,
+        Limits,
+        Stats,
+        FilterErrorBody,
+        Payload,
+        ThresholdAnswer,
+        EfficiencyPayload,
+        League,
+        Player,
+        Grouping,
+        Ties,
+        Method,
+        Format,
+        Locale,
+        Chart,
+        Sort,
+        TeamInequality,
+        LorenzPoint,
+        Outlier,
+        Baseline,
+        TeamCutoff,
+        Band,
+        BandSchema,
+        BandMatrix,
+        TeamBands,
+        RuleSet,
+        Category,
+        ClassifiedPlayer,
+        TeamCompliance,
+        PlayerRef,
+        Edit,
+        Aggregation,
+        Simulation,
+        SimulationResult,
+        RosterRequest,
+        RosterPick,
+        Roster,
+        RosterResult,
+    ))
+)]
+struct ApiDoc;
+
+// The `X-API-Key` header checked by `authenticate`.
+struct ApiKeyScheme;
+
+impl Modify for ApiKeyScheme {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                "api_key",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("x-api-key"))),
+            );
+    }
+}
+
+// The OpenAPI document. Every /salaries path is also listed under
+// /leagues/{league}/salaries, like the router nests it.
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    let league: Parameter = ParameterBuilder::new()
+        .name("league")
+        .parameter_in(ParameterIn::Path)
+        .required(Required::True)
+        .description(Some("League id, e.g. mls"))
+        .schema(Some(ObjectBuilder::new().schema_type(SchemaType::String)))
+        .build();
+    let scoped: Vec<_> = doc
+        .paths
+        .paths
+        .iter()
+        .filter(|(path, _)| path.starts_with("/salaries/"))
+        .map(|(path, item)| {
+            let mut item = item.clone();
+            for operation in item.operations.values_mut() {
+                operation.operation_id = operation
+                    .operation_id
+                    .as_ref()
+                    .map(|id| format!("{}_by_league", id));
+                operation
+                    .parameters
+                    .get_or_insert_with(Vec::new)
+                    .insert(0, league.clone());
+            }
+            (format!("/leagues/{{league}}{}", path), item)
+        })
+        .collect();
+    doc.paths.paths.extend(scoped);
+    doc
+}
+
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    tag = "docs",
+    security(()),
+    responses((status = 200, description = "This OpenAPI document", content_type = "application/json"))
+)]
+async fn get_openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(openapi())
+}
+
+// Swagger UI, loaded from a CDN, over /openapi.json.
+const DOCS_PAGE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>MLS salaries API</title>
+<link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+<div id="docs"></div>
+<script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+<script>SwaggerUIBundle({ url: "/openapi.json", dom_id: "#docs" });</script>
+</body>
+</html>
+"##;
+
+#[utoipa::path(
+    get,
+    path = "/docs",
+    tag = "docs",
+    security(()),
+    responses((status = 200, description = "Interactive API documentation", content_type = "text/html", body = String))
+)]
+async fn get_docs() -> Html<&'static str> {
+    Html(DOCS_PAGE)
+}
+
+// Where `app` mounts the salaries routes: DEFAULT_LEAGUE and any registered
+// league.
+const SALARIES_PREFIXES: [&str; 2] = ["/salaries", "/leagues/:league/salaries"];
+
+// The salaries routes, mounted at each of SALARIES_PREFIXES.
+fn salaries_routes<B>() -> Vec<(&'static str, MethodRouter<(), B>)>
+where
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    vec![
+        ("/filter/:value", get(get_filter)),
+        ("/players", get(get_players)),
+        ("/top/:grouping/:n", get(get_top_n)),
+        ("/inequality", get(get_inequality)),
+        ("/outliers/:grouping", get(get_outliers)),
+        ("/nth/:n", get(get_nth_highest)),
+        ("/threshold/:players", get(get_threshold)),
+        ("/bands", get(get_bands)),
+        ("/rules", get(get_rules)),
+        ("/simulate", post(post_simulate)),
+        ("/roster", post(post_roster)),
+        ("/efficiency", get(get_efficiency).post(post_efficiency)),
+        ("/charts/:chart", get(get_chart).post(post_chart)),
+        ("/export.xlsx", get(get_export)),
+    ]
+}
+
+// Every other route.
+fn other_routes<B>() -> Vec<(&'static str, MethodRouter<(), B>)>
+where
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    vec![
+        ("/", get(root)),
+        ("/teams/:team", get(get_team_page)),
+        ("/leagues", get(get_leagues)),
+        ("/leagues/:league", get(get_league)),
+        ("/admin/leagues/:league/reload", post(post_reload)),
+        ("/diagnostics/cache", get(get_cache_diagnostics)),
+        ("/openapi.json", get(get_openapi)),
+        ("/docs", get(get_docs)),
+        ("/graphql", get(get_graphiql).post(post_graphql)),
+    ]
+}
+
+// The path of every route `app` serves, with `:param` segments.
+pub fn routes() -> Vec<String> {
+    let mut paths: Vec<String> = other_routes::<axum::body::Body>()
+        .into_iter()
+        .map(|(path, _)| path.to_string())
+        .collect();
+    for prefix in SALARIES_PREFIXES {
+        for (path, _) in salaries_routes::<axum::body::Body>() {
+            paths.push(format!("{}{}", prefix, path));
+        }
+    }
+    paths
+}
+
+// The full router. The same salaries routes serve DEFAULT_LEAGUE under
+// /salaries and any registered league under /leagues/:league/salaries.
+//
+// Generic over the request body so the Lambda runtime and tests can each use
+// their own.
+pub fn app<B>(rates: RateTable) -> Router<(), B>
+where
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    let caching = Arc::new(CachePolicy::from_env(&rates));
+    let salaries = salaries_routes()
+        .into_iter()
+        .fold(Router::new(), |router, (path, route)| {
+            router.route(path, route)
+        })
+        .route_layer(middleware::from_fn_with_state(Arc::new(rates), localize))
+        .route_layer(middleware::from_fn_with_state(caching, conditional));
+
+    let app = other_routes()
+        .into_iter()
+        .fold(Router::new(), |router, (path, route)| {
+            router.route(path, route)
+        });
+    SALARIES_PREFIXES
+        .into_iter()
+        .fold(app, |app, prefix| app.nest(prefix, salaries.clone()))
+        .layer(Extension(schema()))
+}
+
+// Marks answers as depending on the key headers, so a shared cache never
+// serves one key's answer (and its rate limit headers) to another caller.
+async fn vary_on_key<B>(request: Request<B>, next: Next<B>) -> Response {
+    let mut response = next.run(request).await;
+    response.headers_mut().append(
+        header::VARY,
+        header::HeaderValue::from_static("x-api-key, authorization"),
+    );
+    response
+}
+
+// `app` behind API keys: requests need a key from `keys` with the route's
+// scope, within the key's rate limit and daily quota.
+pub fn protect<B>(app: Router<(), B>, keys: KeyStore) -> Router<(), B>
+where
+    B: HttpBody + Send + 'static,
+{
+    app.layer(middleware::from_fn_with_state(Arc::new(keys), authenticate))
+        .layer(middleware::from_fn(vary_on_key))
+}
+
+// Refuses `/admin/...` requests, which nobody may make without keys.
+async fn refuse_admin<B>(request: Request<B>, next: Next<B>) -> Response {
+    if Scope::required(request.uri().path()) == Scope::DatasetAdmin {
+        return error(
+            StatusCode::FORBIDDEN,
+            "admin routes are disabled without API keys, set API_KEYS_FILE or API_KEYS",
+        )
+        .into_response();
+    }
+    next.run(request).await
+}
+
+// `app` without API keys: open, except that admin routes are refused.
+pub fn open<B>(app: Router<(), B>) -> Router<(), B>
+where
+    B: HttpBody + Send + 'static,
+{
+    app.layer(middleware::from_fn(refuse_admin))
+}
@@ -0,0 +1,404 @@
+// API keys, their scopes and per-key usage limits.
+//
+// Keys come from a JSON file named by API_KEYS_FILE, or the same JSON in
+// API_KEYS:
+//
+//   [{"key": "s3cret", "name": "finance", "scope": "dataset_admin",
+//     "limits": {"per_minute": 120, "burst": 20, "daily_quota": 50000}}]
+//
+// Each key has a token bucket holding up to `burst` requests, refilled at
+// `per_minute`, and at most `daily_quota` requests per UTC day. `read_only`
+// keys may call every route except the `/admin/...` ones, which need
+// `dataset_admin`. The middleware enforcing this is `app::protect`; only
+// /docs and /openapi.json can be fetched without a key. Without keys
+// `app::open` refuses the admin routes.
+//
+// Buckets and quotas are kept in memory by each Lambda instance. Concurrent
+// instances count separately and a cold start begins afresh, so with N warm
+// instances a key can make up to N times its limits. Use API Gateway usage
+// plans where limits must hold across instances.
+
+use polars::prelude::PolarsError;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DAY: u64 = 24 * 60 * 60;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    ReadOnly,
+    DatasetAdmin,
+}
+
+impl Scope {
+    // The scope a request for `path` needs.
+    pub fn required(path: &str) -> Scope {
+        if path.starts_with("/admin/") {
+            Scope::DatasetAdmin
+        } else {
+            Scope::ReadOnly
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct Limits {
+    pub per_minute: f64,
+    pub burst: f64,
+    // None for no daily limit.
+    pub daily_quota: Option<u64>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            per_minute: 60.0,
+            burst: 10.0,
+            daily_quota: Some(10_000),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ApiKey {
+    pub key: String,
+    // Who holds the key, for the logs.
+    pub name: String,
+    #[serde(default = "read_only")]
+    pub scope: Scope,
+    #[serde(default)]
+    pub limits: Limits,
+}
+
+fn read_only() -> Scope {
+    Scope::ReadOnly
+}
+
+struct Usage {
+    tokens: f64,
+    refilled: SystemTime,
+    // UTC day number of `used`.
+    day: u64,
+    used: u64,
+}
+
+// Why a request was turned away.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rejection {
+    MissingKey,
+    UnknownKey,
+    Forbidden { name: String, required: Scope },
+    RateLimited { retry_after: Duration },
+    QuotaExceeded { retry_after: Duration },
+}
+
+// An admitted request: the key holder and what they have left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Admission {
+    pub name: String,
+    pub remaining: u64,
+    // None for keys without a daily quota.
+    pub quota_remaining: Option<u64>,
+}
+
+pub struct KeyStore {
+    keys: HashMap<String, ApiKey>,
+    usage: Mutex<HashMap<String, Usage>>,
+}
+
+fn invalid(err: impl ToString) -> PolarsError {
+    PolarsError::ComputeError(format!("invalid API keys: {}", err.to_string()).into())
+}
+
+impl KeyStore {
+    pub fn new(keys: Vec<ApiKey>) -> Self {
+        KeyStore {
+            keys: keys.into_iter().map(|key| (key.key.clone(), key)).collect(),
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, PolarsError> {
+        let keys: Vec<ApiKey> = serde_json::from_str(text).map_err(invalid)?;
+        if let Some(key) = keys.iter().find(|key| key.key.is_empty()) {
+            return Err(invalid(format!("key for {} is empty", key.name)));
+        }
+        // Negative or NaN limits would let every request through or break
+        // the Retry-After arithmetic.
+        let valid = |limit: f64| limit.is_finite() && limit >= 0.0;
+        if let Some(key) = keys
+            .iter()
+            .find(|key| !valid(key.limits.per_minute) || !valid(key.limits.burst))
+        {
+            return Err(invalid(format!(
+                "limits for {} must be finite and not negative",
+                key.name
+            )));
+        }
+        Ok(KeyStore::new(keys))
+    }
+
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, PolarsError> {
+        KeyStore::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    // The keys from API_KEYS_FILE or API_KEYS, None when neither is set.
+    pub fn from_env() -> Result<Option<Self>, PolarsError> {
+        if let Ok(path) = std::env::var("API_KEYS_FILE") {
+            return KeyStore::from_file(path).map(Some);
+        }
+        match std::env::var("API_KEYS") {
+            Ok(json) => KeyStore::from_json(&json).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    pub fn admit(&self, key: Option<&str>, required: Scope) -> Result<Admission, Rejection> {
+        self.admit_at(key, required, SystemTime::now())
+    }
+
+    // `admit` at the time `now`: checks the key and its scope, then takes a
+    // token from its bucket and a request from its daily quota.
+    pub fn admit_at(
+        &self,
+        key: Option<&str>,
+        required: Scope,
+        now: SystemTime,
+    ) -> Result<Admission, Rejection> {
+        let key = key.ok_or(Rejection::MissingKey)?;
+        let api_key = self.keys.get(key).ok_or(Rejection::UnknownKey)?;
+        if api_key.scope < required {
+            return Err(Rejection::Forbidden {
+                name: api_key.name.clone(),
+                required,
+            });
+        }
+
+        let limits = api_key.limits;
+        let seconds = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let today = seconds / DAY;
+        let mut usage = self.usage.lock().unwrap();
+        let usage = usage.entry(key.to_string()).or_insert(Usage {
+            tokens: limits.burst,
+            refilled: now,
+            day: today,
+            used: 0,
+        });
+
+        let rate = limits.per_minute / 60.0;
+        let elapsed = now.duration_since(usage.refilled).unwrap_or_default();
+        usage.tokens = (usage.tokens + elapsed.as_secs_f64() * rate).min(limits.burst);
+        usage.refilled = now;
+        if usage.day != today {
+            usage.day = today;
+            usage.used = 0;
+        }
+
+        if limits.daily_quota.is_some_and(|quota| usage.used >= quota) {
+            return Err(Rejection::QuotaExceeded {
+                retry_after: Duration::from_secs((today + 1) * DAY - seconds),
+            });
+        }
+        if usage.tokens < 1.0 {
+            let wait = if rate > 0.0 {
+                (1.0 - usage.tokens) / rate
+            } else {
+                DAY as f64
+            };
+            return Err(Rejection::RateLimited {
+                retry_after: Duration::from_secs_f64(wait.ceil()),
+            });
+        }
+        usage.tokens -= 1.0;
+        usage.used += 1;
+        Ok(Admission {
+            name: api_key.name.clone(),
+            remaining: usage.tokens as u64,
+            quota_remaining: limits.daily_quota.map(|quota| quota - usage.used),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{app, open, protect};
+    use crate::currency::RateTable;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use tower::ServiceExt;
+
+    const KEYS: &str = r#"[
+        {"key": "reader", "name": "web",
+         "limits": {"per_minute": 60, "burst": 2, "daily_quota": 3}},
+        {"key": "admin", "name": "ops", "scope": "dataset_admin"}
+    ]"#;
+
+    fn keys() -> KeyStore {
+        KeyStore::from_json(KEYS).unwrap()
+    }
+
+    // Noon UTC on some day, plus `seconds`.
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(19_000 * DAY + DAY / 2 + seconds)
+    }
+
+    fn admit(keys: &KeyStore, key: &str, now: SystemTime) -> Result<Admission, Rejection> {
+        keys.admit_at(Some(key), Scope::ReadOnly, now)
+    }
+
+    #[test]
+    fn buckets_refill_at_the_per_minute_rate() {
+        let keys = KeyStore::from_json(
+            r#"[{"key": "k", "name": "n", "limits": {"per_minute": 60, "burst": 2}}]"#,
+        )
+        .unwrap();
+        assert_eq!(admit(&keys, "k", at(0)).unwrap().remaining, 1);
+        assert_eq!(admit(&keys, "k", at(0)).unwrap().remaining, 0);
+        assert_eq!(
+            admit(&keys, "k", at(0)),
+            Err(Rejection::RateLimited {
+                retry_after: Duration::from_secs(1)
+            })
+        );
+        // One token a second, up to the burst.
+        assert!(admit(&keys, "k", at(1)).is_ok());
+        assert!(admit(&keys, "k", at(1)).is_err());
+        assert_eq!(admit(&keys, "k", at(60)).unwrap().remaining, 1);
+    }
+
+    #[test]
+    fn daily_quotas_reset_at_utc_midnight() {
+        let keys = keys();
+        for (second, left) in [(0, 2), (10, 1), (20, 0)] {
+            let admission = admit(&keys, "reader", at(second)).unwrap();
+            assert_eq!(admission.quota_remaining, Some(left));
+        }
+        // Half a day to go until midnight.
+        assert_eq!(
+            admit(&keys, "reader", at(30)),
+            Err(Rejection::QuotaExceeded {
+                retry_after: Duration::from_secs(DAY / 2 - 30)
+            })
+        );
+        let tomorrow = admit(&keys, "reader", at(DAY / 2)).unwrap();
+        assert_eq!(tomorrow.quota_remaining, Some(2));
+    }
+
+    #[test]
+    fn keys_need_the_scope_of_the_route() {
+        let keys = keys();
+        assert_eq!(
+            Scope::required("/admin/leagues/mls/reload"),
+            Scope::DatasetAdmin
+        );
+        assert_eq!(
+            Scope::required("/leagues/mls/salaries/bands"),
+            Scope::ReadOnly
+        );
+        assert_eq!(
+            keys.admit_at(Some("reader"), Scope::DatasetAdmin, at(0)),
+            Err(Rejection::Forbidden {
+                name: "web".to_string(),
+                required: Scope::DatasetAdmin
+            })
+        );
+        assert!(keys
+            .admit_at(Some("admin"), Scope::DatasetAdmin, at(0))
+            .is_ok());
+        assert!(keys.admit_at(Some("admin"), Scope::ReadOnly, at(0)).is_ok());
+    }
+
+    #[test]
+    fn requests_need_a_known_key() {
+        let keys = keys();
+        assert_eq!(
+            keys.admit_at(None, Scope::ReadOnly, at(0)),
+            Err(Rejection::MissingKey)
+        );
+        assert_eq!(admit(&keys, "guess", at(0)), Err(Rejection::UnknownKey));
+        assert!(KeyStore::from_json(r#"[{"key": "", "name": "blank"}]"#).is_err());
+    }
+
+    #[test]
+    fn limits_must_be_finite_and_not_negative() {
+        for limits in [
+            r#"{"per_minute": -1}"#,
+            r#"{"burst": -5}"#,
+            r#"{"per_minute": -0.5, "burst": 2}"#,
+        ] {
+            let json = format!(r#"[{{"key": "k", "name": "n", "limits": {}}}]"#, limits);
+            let err = KeyStore::from_json(&json).err().unwrap();
+            assert_eq!(
+                err.to_string(),
+                "invalid API keys: limits for n must be finite and not negative",
+                "{}",
+                limits
+            );
+        }
+        // A key that may never make a request is still a valid key.
+        let keys = KeyStore::from_json(
+            r#"[{"key": "k", "name": "n", "limits": {"per_minute": 0, "burst": 0}}]"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            admit(&keys, "k", at(0)),
+            Err(Rejection::RateLimited { .. })
+        ));
+    }
+
+    async fn status(app: axum::Router<(), Body>, method: Method, uri: &str) -> StatusCode {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn only_the_docs_are_public() {
+        let protected = || protect(app::<Body>(RateTable::default()), keys());
+        for uri in ["/docs", "/openapi.json"] {
+            assert_eq!(
+                status(protected(), Method::GET, uri).await,
+                StatusCode::OK,
+                "{}",
+                uri
+            );
+        }
+        // The report and team pages show salaries, so they need a key too.
+        for uri in [
+            "/",
+            "/teams/Toronto%20FC",
+            "/graphql",
+            "/salaries/bands",
+            "/leagues",
+            "/leagues/mls/salaries/top/team/3",
+        ] {
+            let status = status(protected(), Method::GET, uri).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", uri);
+        }
+        assert_eq!(
+            status(protected(), Method::POST, "/graphql").await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn admin_routes_are_refused_without_keys() {
+        let open = || open(app::<Body>(RateTable::default()));
+        assert_eq!(
+            status(open(), Method::POST, "/admin/leagues/mls/reload").await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(open(), Method::GET, "/salaries/top/team/3").await,
+            StatusCode::OK
+        );
+    }
+}
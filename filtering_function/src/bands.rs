@@ -0,0 +1,239 @@
+// Salary bands: count players and payroll per team for every band of a
+// schema in a single pass, instead of one `calculate` call per threshold.
+//
+// A band covers `lower <= salary < upper`; a missing bound is open-ended.
+
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+// MLS 2023 roster figures used by the `mls` preset.
+pub const SENIOR_MINIMUM: f64 = 85_444.0;
+pub const MAX_BUDGET_CHARGE: f64 = 651_250.0;
+pub const TAM_MAXIMUM: f64 = 1_612_500.0;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct Band {
+    pub label: String,
+    pub lower: Option<f64>,
+    pub upper: Option<f64>,
+}
+
+impl Band {
+    fn contains(&self) -> Expr {
+        let salary = col("salary");
+        match (self.lower, self.upper) {
+            (Some(lower), Some(upper)) => {
+                salary.clone().gt_eq(lit(lower)).and(salary.lt(lit(upper)))
+            }
+            (Some(lower), None) => salary.gt_eq(lit(lower)),
+            (None, Some(upper)) => salary.lt(lit(upper)),
+            (None, None) => lit(true),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct BandSchema {
+    pub bands: Vec<Band>,
+}
+
+impl BandSchema {
+    // Builds contiguous bands from ascending edges, e.g. `[100k, 500k]`
+    // gives `<100k`, `100k-500k` and `>=500k`. Edges must be finite and
+    // strictly ascending, or some bands would be empty or overlap.
+    pub fn from_edges(edges: &[f64]) -> Result<Self, PolarsError> {
+        let ascending = edges.windows(2).all(|pair| pair[0] < pair[1]);
+        if !ascending || !edges.iter().all(|edge| edge.is_finite()) {
+            return Err(PolarsError::ComputeError(
+                format!(
+                    "band edges must be finite and strictly ascending, got {:?}",
+                    edges
+                )
+                .into(),
+            ));
+        }
+        let mut bands = Vec::with_capacity(edges.len() + 1);
+        let mut lower = None;
+        for &edge in edges {
+            let label = match lower {
+                Some(lower) => format!("{}-{}", compact(lower), compact(edge)),
+                None => format!("<{}", compact(edge)),
+            };
+            bands.push(Band {
+                label,
+                lower,
+                upper: Some(edge),
+            });
+            lower = Some(edge);
+        }
+        bands.push(Band {
+            label: match lower {
+                Some(lower) => format!(">={}", compact(lower)),
+                None => "all".to_string(),
+            },
+            lower,
+            upper: None,
+        });
+        Ok(BandSchema { bands })
+    }
+
+    // <100k, 100k-500k, 500k-1M, 1M-2M, >=2M
+    pub fn standard() -> Self {
+        Self::from_edges(&[100_000.0, 500_000.0, 1_000_000.0, 2_000_000.0]).unwrap()
+    }
+
+    // MLS roster categories: below the senior minimum, senior roster budget
+    // range, the targeted allocation money (TAM) range and the designated
+    // player range.
+    pub fn mls() -> Self {
+        let band = |label: &str, lower, upper| Band {
+            label: label.to_string(),
+            lower,
+            upper,
+        };
+        BandSchema {
+            bands: vec![
+                band("below-senior-minimum", None, Some(SENIOR_MINIMUM)),
+                band("senior", Some(SENIOR_MINIMUM), Some(MAX_BUDGET_CHARGE)),
+                band("tam-range", Some(MAX_BUDGET_CHARGE), Some(TAM_MAXIMUM)),
+                band("designated-player-range", Some(TAM_MAXIMUM), None),
+            ],
+        }
+    }
+
+    // A player matching several overlapping bands is put in the first one.
+    fn label(&self) -> Expr {
+        self.bands
+            .iter()
+            .rev()
+            .fold(lit(NULL).cast(DataType::Utf8), |otherwise, band| {
+                when(band.contains())
+                    .then(lit(band.label.clone()))
+                    .otherwise(otherwise)
+            })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct TeamBands {
+    pub team: String,
+    // Indexed like `BandMatrix::bands`.
+    pub counts: Vec<u32>,
+    pub payroll: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct BandMatrix {
+    pub bands: Vec<String>,
+    pub teams: Vec<TeamBands>,
+}
+
+// Counts players and sums payroll for every team x band cell. Players outside
+// every band are left out.
+pub fn band_matrix(df: &DataFrame, schema: &BandSchema) -> Result<BandMatrix, PolarsError> {
+    let df = df
+        .clone()
+        .lazy()
+        .with_column(schema.label().alias("band"))
+        .filter(col("band").is_not_null())
+        .groupby([col("team"), col("band")])
+        .agg([
+            col("salary").count().alias("players"),
+            col("salary").sum().alias("payroll"),
+        ])
+        .collect()?;
+
+    let labels: Vec<String> = schema.bands.iter().map(|band| band.label.clone()).collect();
+    let mut teams: BTreeMap<String, TeamBands> = BTreeMap::new();
+    let team_names = df.column("team")?.utf8()?;
+    let bands = df.column("band")?.utf8()?;
+    let players = df.column("players")?.u32()?;
+    let payroll = df.column("payroll")?.f64()?;
+    for i in 0..df.height() {
+        let (Some(team), Some(band)) = (team_names.get(i), bands.get(i)) else {
+            continue;
+        };
+        let Some(index) = labels.iter().position(|label| label == band) else {
+            continue;
+        };
+        let row = teams.entry(team.to_string()).or_insert_with(|| TeamBands {
+            team: team.to_string(),
+            counts: vec![0; labels.len()],
+            payroll: vec![0.0; labels.len()],
+        });
+        row.counts[index] += players.get(i).unwrap_or(0);
+        row.payroll[index] += payroll.get(i).unwrap_or(0.0);
+    }
+
+    Ok(BandMatrix {
+        bands: labels,
+        teams: teams.into_values().collect(),
+    })
+}
+
+// 100000 -> "100k", 1250000 -> "1.25M"
+fn compact(value: f64) -> String {
+    let (scaled, suffix) = if value.abs() >= 1_000_000.0 {
+        (value / 1_000_000.0, "M")
+    } else if value.abs() >= 1_000.0 {
+        (value / 1_000.0, "k")
+    } else {
+        (value, "")
+    };
+    let digits = format!("{:.2}", scaled);
+    let digits = digits.trim_end_matches('0').trim_end_matches('.');
+    format!("{}{}", digits, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::app;
+    use crate::currency::RateTable;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    #[test]
+    fn edges_give_contiguous_bands() {
+        let schema = BandSchema::from_edges(&[100_000.0, 500_000.0]).unwrap();
+        let labels: Vec<_> = schema
+            .bands
+            .iter()
+            .map(|band| band.label.as_str())
+            .collect();
+        assert_eq!(labels, ["<100k", "100k-500k", ">=500k"]);
+        assert_eq!(BandSchema::from_edges(&[]).unwrap().bands[0].label, "all");
+    }
+
+    #[test]
+    fn edges_must_be_finite_and_strictly_ascending() {
+        for edges in [
+            vec![500_000.0, 100_000.0],
+            vec![100_000.0, 100_000.0],
+            vec![f64::NAN],
+            vec![100_000.0, f64::INFINITY],
+            vec![f64::NEG_INFINITY, 100_000.0],
+        ] {
+            assert!(BandSchema::from_edges(&edges).is_err(), "{:?}", edges);
+        }
+    }
+
+    #[tokio::test]
+    async fn bad_edges_are_a_bad_request() {
+        for edges in ["500000,100000", "100000,nan", "inf", "100000,100000"] {
+            let uri = format!("/salaries/bands?edges={}", edges);
+            let request = Request::builder().uri(&uri).body(Body::empty()).unwrap();
+            let response = app::<Body>(RateTable::default())
+                .oneshot(request)
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", edges);
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert!(body["error"].is_string(), "{}", edges);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// Prints the players matching a filter expression (see filter.rs).
+//
+//   salary-filter 'team == "LAFC" and salary > 500000' [salaries.csv]
+//
+// Reads the bundled MLS salaries unless a CSV with the same columns is
+// given. Exits with status 2 and an underlined error for a bad filter.
+
+use polars::prelude::*;
+use polars_lambda_axum::filter::compile_filter;
+use polars_lambda_axum::{load_salaries, read_salaries};
+use std::process::ExitCode;
+
+fn main() -> Result<ExitCode, PolarsError> {
+    let mut args = std::env::args().skip(1);
+    let Some(source) = args.next() else {
+        eprintln!("usage: salary-filter <filter> [salaries.csv]");
+        return Ok(ExitCode::from(2));
+    };
+    let df = match args.next() {
+        Some(path) => read_salaries(&std::fs::read_to_string(path)?)?,
+        None => load_salaries()?,
+    };
+    let expr = match compile_filter(&source, &df.schema()) {
+        Ok(expr) => expr,
+        Err(err) => {
+            eprintln!("{}", err.render(&source));
+            return Ok(ExitCode::from(2));
+        }
+    };
+    let matched = df.lazy().filter(expr).collect()?;
+    std::env::set_var("POLARS_FMT_MAX_ROWS", "-1");
+    println!("{}", matched);
+    Ok(ExitCode::SUCCESS)
+}
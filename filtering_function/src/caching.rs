@@ -0,0 +1,358 @@
+// HTTP caching of the salary routes.
+//
+// A GET answer depends only on the league's data, the path and the query
+// (and the exchange rates behind `currency`), so its strong ETag hashes
+// exactly those: the league and its dataset version, the path, the query
+// parameters sorted and decoded, and the rate table. The one route reading
+// other data, `/efficiency` with the PLAYER_STATS_CSV file, also hashes
+// that file's contents, so replacing it changes the ETag. A request whose
+// `If-None-Match` holds the current ETag gets a bodiless 304 without the
+// handler running; one holding `*` gets it when the handler answers 200.
+//
+// Successful answers carry CACHE_CONTROL, "no-cache" by default: shared
+// caches may store answers but revalidate every use, so API key checks
+// still happen. Set e.g. "public, max-age=300" to let CloudFront or the API
+// Gateway cache serve repeats. When keys are required, `app::protect` adds
+// `Vary: x-api-key, authorization` so caches key answers on the caller's
+// key; configure the cache to forward those headers.
+
+use crate::currency::RateTable;
+use crate::leagues::{league, DEFAULT_LEAGUE};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+pub const DEFAULT_CACHE_CONTROL: &str = "no-cache";
+
+pub struct CachePolicy {
+    cache_control: HeaderValue,
+    // Identifies the rate table, which `currency` answers depend on.
+    rates: String,
+}
+
+impl CachePolicy {
+    pub fn new(cache_control: HeaderValue, rates: &RateTable) -> Self {
+        let mut table: Vec<_> = rates.rates.iter().collect();
+        table.sort_by(|a, b| a.0.cmp(b.0));
+        let rates = format!("{}:{:?}", rates.base, table);
+        CachePolicy {
+            cache_control,
+            rates,
+        }
+    }
+
+    // CACHE_CONTROL, or DEFAULT_CACHE_CONTROL when unset or not a valid
+    // header value.
+    pub fn from_env(rates: &RateTable) -> Self {
+        let cache_control = std::env::var("CACHE_CONTROL")
+            .ok()
+            .and_then(|value| HeaderValue::from_str(&value).ok())
+            .unwrap_or(HeaderValue::from_static(DEFAULT_CACHE_CONTROL));
+        CachePolicy::new(cache_control, rates)
+    }
+
+    // The ETag of the answer to `path` with `query` from the league's data
+    // at `version`.
+    pub fn etag(
+        &self,
+        league: &str,
+        version: &str,
+        path: &str,
+        query: &[(String, String)],
+    ) -> String {
+        let mut query = query.to_vec();
+        query.sort();
+        let mut hasher = DefaultHasher::new();
+        (league, version, path, query, &self.rates).hash(&mut hasher);
+        format!("\"{:016x}\"", hasher.finish())
+    }
+}
+
+// The version of data `path` reads besides the league's: a hash of the
+// PLAYER_STATS_CSV file for `/efficiency`, empty for other routes. None
+// when the file can't be read, which the handler reports.
+fn other_data_version(path: &str) -> Option<String> {
+    if !path.ends_with("/efficiency") {
+        return Some(String::new());
+    }
+    let stats = std::fs::read(std::env::var("PLAYER_STATS_CSV").ok()?).ok()?;
+    let mut hasher = DefaultHasher::new();
+    stats.hash(&mut hasher);
+    Some(format!("+stats:{:016x}", hasher.finish()))
+}
+
+// Whether an `If-None-Match` header value names `etag`. The comparison is
+// weak, as RFC 9110 asks for this header.
+pub fn none_match(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.trim() == "*"
+        || if_none_match
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == etag)
+}
+
+// Answers conditional GETs and labels fresh answers with their ETag and
+// Cache-Control.
+pub async fn conditional<B>(
+    State(policy): State<Arc<CachePolicy>>,
+    path: Option<Path<HashMap<String, String>>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        return next.run(request).await;
+    }
+    let id = path
+        .as_ref()
+        .and_then(|Path(params)| params.get("league"))
+        .map_or(DEFAULT_LEAGUE, String::as_str);
+    // Unknown leagues, unloadable data and malformed queries are left to the
+    // handler to explain.
+    let league = league(id);
+    let version = league
+        .and_then(|league| league.version().ok())
+        .zip(other_data_version(request.uri().path()))
+        .map(|(league, other)| format!("{}{}", league, other));
+    let query = Query::<Vec<(String, String)>>::try_from_uri(request.uri()).ok();
+    let (Some(league), Some(version), Some(Query(query))) = (league, version, query) else {
+        return next.run(request).await;
+    };
+    let etag = policy.etag(league.id, &version, request.uri().path(), &query);
+    let etag = HeaderValue::from_str(&etag).unwrap();
+
+    // `*` matches any current answer, and only the handler can tell
+    // whether there is one (rather than, say, a 400).
+    let (mut any, mut not_modified) = (false, false);
+    for value in request.headers().get_all(header::IF_NONE_MATCH) {
+        match value.to_str().map(str::trim) {
+            Ok("*") => any = true,
+            Ok(tags) => not_modified |= none_match(tags, etag.to_str().unwrap()),
+            Err(_) => {}
+        }
+    }
+    let mut response = if not_modified {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        next.run(request).await
+    };
+    if any && response.status() == StatusCode::OK {
+        response = StatusCode::NOT_MODIFIED.into_response();
+    }
+    if matches!(response.status(), StatusCode::OK | StatusCode::NOT_MODIFIED) {
+        let headers = response.headers_mut();
+        headers.insert(header::ETAG, etag);
+        headers.insert(header::CACHE_CONTROL, policy.cache_control.clone());
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::app;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    async fn get(uri: &str, if_none_match: Option<&str>) -> Response {
+        let mut request = Request::builder().uri(uri);
+        if let Some(tags) = if_none_match {
+            request = request.header(header::IF_NONE_MATCH, tags);
+        }
+        app::<Body>(RateTable::default())
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    fn etag(response: &Response) -> Option<String> {
+        let etag = response.headers().get(header::ETAG)?;
+        Some(etag.to_str().unwrap().to_string())
+    }
+
+    async fn body_len(response: Response) -> usize {
+        hyper::body::to_bytes(response.into_body())
+            .await
+            .unwrap()
+            .len()
+    }
+
+    #[test]
+    fn etags_hash_every_input_but_the_query_order() {
+        let rates = RateTable::default();
+        let policy = CachePolicy::new(HeaderValue::from_static("no-cache"), &rates);
+        let query = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let base = policy.etag(
+            "mls",
+            "v1",
+            "/salaries/rules",
+            &query(&[("a", "1"), ("b", "2")]),
+        );
+        assert!(base.starts_with('"') && base.ends_with('"'));
+        assert_eq!(
+            base,
+            policy.etag(
+                "mls",
+                "v1",
+                "/salaries/rules",
+                &query(&[("b", "2"), ("a", "1")])
+            )
+        );
+        for other in [
+            policy.etag(
+                "nwsl",
+                "v1",
+                "/salaries/rules",
+                &query(&[("a", "1"), ("b", "2")]),
+            ),
+            policy.etag(
+                "mls",
+                "v2",
+                "/salaries/rules",
+                &query(&[("a", "1"), ("b", "2")]),
+            ),
+            policy.etag(
+                "mls",
+                "v1",
+                "/salaries/bands",
+                &query(&[("a", "1"), ("b", "2")]),
+            ),
+            policy.etag(
+                "mls",
+                "v1",
+                "/salaries/rules",
+                &query(&[("a", "1"), ("b", "3")]),
+            ),
+        ] {
+            assert_ne!(base, other);
+        }
+    }
+
+    #[test]
+    fn if_none_match_compares_weakly() {
+        let etag = "\"abc\"";
+        assert!(none_match("\"abc\"", etag));
+        assert!(none_match("W/\"abc\"", etag));
+        assert!(none_match("\"x\", W/\"abc\"", etag));
+        assert!(none_match(" * ", etag));
+        assert!(!none_match("\"abcd\"", etag));
+        assert!(!none_match("abc", etag));
+    }
+
+    #[tokio::test]
+    async fn current_etags_get_a_bodiless_304() {
+        let uri = "/salaries/rules?max_dps=3&salary_budget=5210000";
+        let fresh = get(uri, None).await;
+        assert_eq!(fresh.status(), StatusCode::OK);
+        assert_eq!(
+            fresh.headers()[header::CACHE_CONTROL],
+            DEFAULT_CACHE_CONTROL
+        );
+        let tag = etag(&fresh).unwrap();
+
+        for tags in [
+            tag.clone(),
+            format!("W/{}", tag),
+            format!("\"other\", {}", tag),
+            "*".to_string(),
+        ] {
+            let cached = get(uri, Some(&tags)).await;
+            assert_eq!(cached.status(), StatusCode::NOT_MODIFIED, "{}", tags);
+            assert_eq!(etag(&cached), Some(tag.clone()));
+            assert_eq!(
+                cached.headers()[header::CACHE_CONTROL],
+                DEFAULT_CACHE_CONTROL
+            );
+            assert_eq!(body_len(cached).await, 0);
+        }
+
+        // The same parameters in another order are the same answer.
+        let reordered = get(
+            "/salaries/rules?salary_budget=5210000&max_dps=3",
+            Some(&tag),
+        )
+        .await;
+        assert_eq!(reordered.status(), StatusCode::NOT_MODIFIED);
+
+        let changed = get(
+            "/salaries/rules?max_dps=4&salary_budget=5210000",
+            Some(&tag),
+        )
+        .await;
+        assert_eq!(changed.status(), StatusCode::OK);
+        assert_ne!(etag(&changed), Some(tag));
+    }
+
+    #[tokio::test]
+    async fn failures_are_not_labelled() {
+        for (uri, status) in [
+            ("/leagues/nope/salaries/rules", StatusCode::NOT_FOUND),
+            (
+                "/salaries/players?filter=salry%20%3E%201",
+                StatusCode::BAD_REQUEST,
+            ),
+            ("/salaries/rules?max_dps=lots", StatusCode::BAD_REQUEST),
+        ] {
+            let response = get(uri, Some("*")).await;
+            assert_eq!(response.status(), status, "{}", uri);
+            assert_eq!(etag(&response), None, "{}", uri);
+            assert!(response.headers().get(header::CACHE_CONTROL).is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn replacing_the_stats_file_changes_the_efficiency_etag() {
+        let path = std::env::temp_dir().join(format!("stats-{}.csv", std::process::id()));
+        let stats = |goals: u32| {
+            let csv = format!(
+                "first_name,last_name,minutes,goals,assists\nLuis,Abram,900,{},1\n",
+                goals
+            );
+            std::fs::write(&path, csv).unwrap();
+        };
+        std::env::set_var("PLAYER_STATS_CSV", &path);
+
+        stats(2);
+        let before = get("/salaries/efficiency", None).await;
+        assert_eq!(before.status(), StatusCode::OK);
+        let tag = etag(&before).unwrap();
+        let same = get("/salaries/efficiency", Some(&tag)).await;
+        assert_eq!(same.status(), StatusCode::NOT_MODIFIED);
+
+        stats(3);
+        let after = get("/salaries/efficiency", Some(&tag)).await;
+        assert_eq!(after.status(), StatusCode::OK);
+        assert_ne!(etag(&after), Some(tag));
+    }
+
+    #[tokio::test]
+    async fn answers_vary_on_the_key_when_keys_are_required() {
+        let keys = crate::auth::KeyStore::from_json(r#"[{"key": "k", "name": "n"}]"#).unwrap();
+        let request = Request::builder()
+            .uri("/salaries/rules?max_dps=2")
+            .header("x-api-key", "k")
+            .body(Body::empty())
+            .unwrap();
+        let response = crate::app::protect(app::<Body>(RateTable::default()), keys)
+            .oneshot(request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::VARY], "x-api-key, authorization");
+
+        // Without keys every caller gets the same answer.
+        let open = get("/salaries/rules?max_dps=2", None).await;
+        assert!(open.headers().get(header::VARY).is_none());
+    }
+}
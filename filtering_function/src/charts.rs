@@ -0,0 +1,585 @@
+// SVG rendering of salary aggregates.
+//
+// Three chart types, all drawn as self-contained SVG documents:
+//  - bar:       one horizontal bar per label, e.g. players above a threshold
+//               per team
+//  - box:       one horizontal box plot per group (quartiles, whiskers at
+//               1.5 × IQR, dots for the points beyond them)
+//  - histogram: the distribution of a set of values in equal-width bins
+//
+// `labelled_values` pulls (label, value) pairs out of any aggregation result
+// serialized to JSON, so every `simulate::Aggregation` can be charted.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::Write;
+use utoipa::{IntoParams, ToSchema};
+
+const MIN_SIZE: u32 = 100;
+const MAX_SIZE: u32 = 4000;
+const COLOR: &str = "#3b6ea5";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Chart {
+    Bar,
+    Box,
+    Histogram,
+}
+
+// Order of the bars or boxes. Box plots sort by median.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Sort {
+    #[default]
+    Desc,
+    Asc,
+    Label,
+    // Keep the order of the data.
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(default)]
+pub struct ChartOptions {
+    // Pixels, clamped to 100..=4000.
+    pub width: u32,
+    pub height: u32,
+    pub sort: Sort,
+    // Histogram bin count.
+    pub bins: usize,
+    pub title: Option<String>,
+}
+
+impl Default for ChartOptions {
+    fn default() -> Self {
+        ChartOptions {
+            width: 640,
+            height: 400,
+            sort: Sort::default(),
+            bins: 20,
+            title: None,
+        }
+    }
+}
+
+impl ChartOptions {
+    fn size(&self) -> (f64, f64) {
+        (
+            self.width.clamp(MIN_SIZE, MAX_SIZE) as f64,
+            self.height.clamp(MIN_SIZE, MAX_SIZE) as f64,
+        )
+    }
+}
+
+// Collects (label, value) pairs from an aggregation result:
+//  - an object of numbers, like `{"LAFC": 4}`, gives one pair per key
+//  - an array of objects gives one pair per object with a `label` field and
+//    a numeric `field`; an array of numbers in `field` is summed, so band
+//    matrix rows chart their total
+//  - any other object is searched for the first member that gives pairs, so
+//    `{"bands": .., "teams": [..]}` charts its teams
+pub fn labelled_values(value: &Value, label: &str, field: &str) -> Vec<(String, f64)> {
+    match value {
+        Value::Object(members) if members.values().all(Value::is_number) => members
+            .iter()
+            .filter_map(|(key, value)| Some((key.clone(), value.as_f64()?)))
+            .collect(),
+        Value::Object(members) => members
+            .values()
+            .map(|member| labelled_values(member, label, field))
+            .find(|pairs| !pairs.is_empty())
+            .unwrap_or_default(),
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| {
+                let label = match item.get(label)? {
+                    Value::String(label) => label.clone(),
+                    other => other.to_string(),
+                };
+                let value = match item.get(field)? {
+                    Value::Array(values) => values.iter().filter_map(Value::as_f64).sum(),
+                    value => value.as_f64()?,
+                };
+                Some((label, value))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// "Nice" tick positions (steps of 1, 2 or 5 × 10^k) covering min..=max.
+fn ticks(min: f64, max: f64, count: usize) -> Vec<f64> {
+    let (min, max) = if max > min {
+        (min, max)
+    } else {
+        (min, min + 1.0)
+    };
+    let rough = (max - min) / count.max(1) as f64;
+    let magnitude = 10f64.powf(rough.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= rough)
+        .unwrap_or(10.0 * magnitude);
+    let first = (min / step).floor() as i64;
+    let last = (max / step).ceil() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+// Short axis label: 1.5M, 250k, 12.
+fn short(value: f64) -> String {
+    let (scaled, suffix) = match value.abs() {
+        v if v >= 1e9 => (value / 1e9, "B"),
+        v if v >= 1e6 => (value / 1e6, "M"),
+        v if v >= 1e3 => (value / 1e3, "k"),
+        _ => (value, ""),
+    };
+    let digits = format!("{:.2}", scaled);
+    let digits = digits.trim_end_matches('0').trim_end_matches('.');
+    format!("{}{}", digits, suffix)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+struct Canvas {
+    svg: String,
+    width: f64,
+    height: f64,
+}
+
+impl Canvas {
+    fn new(options: &ChartOptions) -> Self {
+        let (width, height) = options.size();
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"11\">\n\
+             <rect width=\"{w}\" height=\"{h}\" fill=\"white\"/>\n",
+            w = width,
+            h = height
+        );
+        if let Some(title) = &options.title {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"18\" text-anchor=\"middle\" font-size=\"14\" font-weight=\"bold\">{}</text>",
+                width / 2.0,
+                escape(title)
+            );
+        }
+        Canvas { svg, width, height }
+    }
+
+    fn text(&mut self, x: f64, y: f64, anchor: &str, text: &str) {
+        let _ = writeln!(
+            self.svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"{}\">{}</text>",
+            x,
+            y,
+            anchor,
+            escape(text)
+        );
+    }
+
+    fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, stroke: &str) {
+        let _ = writeln!(
+            self.svg,
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\"/>",
+            x1, y1, x2, y2, stroke
+        );
+    }
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, tooltip: &str) {
+        let _ = writeln!(
+            self.svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"><title>{}</title></rect>",
+            x,
+            y,
+            width.max(0.0),
+            height.max(0.0),
+            COLOR,
+            escape(tooltip)
+        );
+    }
+
+    fn no_data(mut self) -> String {
+        let (x, y) = (self.width / 2.0, self.height / 2.0);
+        self.text(x, y, "middle", "No data");
+        self.finish()
+    }
+
+    fn finish(mut self) -> String {
+        self.svg.push_str("</svg>\n");
+        self.svg
+    }
+}
+
+// Top of the plot area, below the title if any.
+fn top(options: &ChartOptions) -> f64 {
+    if options.title.is_some() {
+        32.0
+    } else {
+        12.0
+    }
+}
+
+// Room for the longest label left of the plot, at most a third of the chart.
+fn label_margin<'a>(labels: impl Iterator<Item = &'a str>, width: f64) -> f64 {
+    let longest = labels.map(|label| label.chars().count()).max().unwrap_or(0);
+    (longest as f64 * 6.5 + 12.0).min(width / 3.0)
+}
+
+// Vertical grid lines with labels below the plot for a horizontal axis.
+fn x_axis(canvas: &mut Canvas, ticks: &[f64], x: impl Fn(f64) -> f64, top: f64, bottom: f64) {
+    for tick in ticks {
+        canvas.line(x(*tick), top, x(*tick), bottom, "#ddd");
+        canvas.text(x(*tick), bottom + 14.0, "middle", &short(*tick));
+    }
+}
+
+fn sort_by<T>(items: &mut [(String, T)], sort: Sort, key: impl Fn(&T) -> f64) {
+    match sort {
+        Sort::Desc => items.sort_by(|(_, a), (_, b)| key(b).total_cmp(&key(a))),
+        Sort::Asc => items.sort_by(|(_, a), (_, b)| key(a).total_cmp(&key(b))),
+        Sort::Label => items.sort_by(|(a, _), (b, _)| a.cmp(b)),
+        Sort::None => {}
+    }
+}
+
+pub fn bar_chart(data: &[(String, f64)], options: &ChartOptions) -> String {
+    let mut canvas = Canvas::new(options);
+    if data.is_empty() {
+        return canvas.no_data();
+    }
+    let mut data = data.to_vec();
+    sort_by(&mut data, options.sort, |value| *value);
+
+    let left = label_margin(data.iter().map(|(label, _)| label.as_str()), canvas.width);
+    let (top, bottom, right) = (top(options), canvas.height - 24.0, canvas.width - 16.0);
+    let low = data.iter().map(|(_, v)| *v).fold(0.0, f64::min);
+    let high = data.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+    let ticks = ticks(low, high, 5);
+    let (start, end) = (ticks[0], ticks[ticks.len() - 1]);
+    let x = |value: f64| left + (value - start) / (end - start) * (right - left);
+    x_axis(&mut canvas, &ticks, x, top, bottom);
+
+    let band = (bottom - top) / data.len() as f64;
+    for (i, (label, value)) in data.iter().enumerate() {
+        let y = top + i as f64 * band;
+        let (from, to) = (x(value.min(0.0)), x(value.max(0.0)));
+        canvas.rect(
+            from,
+            y + band * 0.15,
+            to - from,
+            band * 0.7,
+            &format!("{}: {}", label, value),
+        );
+        canvas.text(left - 6.0, y + band / 2.0 + 4.0, "end", label);
+    }
+    canvas.line(x(0.0), top, x(0.0), bottom, "#333");
+    canvas.finish()
+}
+
+// Linear interpolation between the closest ranks of sorted values.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let (below, above) = (position.floor() as usize, position.ceil() as usize);
+    sorted[below] + (sorted[above] - sorted[below]) * (position - below as f64)
+}
+
+struct Summary {
+    q1: f64,
+    median: f64,
+    q3: f64,
+    low: f64,
+    high: f64,
+    outliers: Vec<f64>,
+}
+
+impl Summary {
+    fn new(values: &[f64]) -> Self {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let (q1, median, q3) = (
+            quantile(&sorted, 0.25),
+            quantile(&sorted, 0.5),
+            quantile(&sorted, 0.75),
+        );
+        let fence = 1.5 * (q3 - q1);
+        let inside = |v: &&f64| **v >= q1 - fence && **v <= q3 + fence;
+        Summary {
+            q1,
+            median,
+            q3,
+            low: sorted.iter().find(inside).copied().unwrap_or(q1),
+            high: sorted.iter().rev().find(inside).copied().unwrap_or(q3),
+            outliers: sorted.iter().filter(|v| !inside(v)).copied().collect(),
+        }
+    }
+}
+
+pub fn box_plot(groups: &[(String, Vec<f64>)], options: &ChartOptions) -> String {
+    let mut canvas = Canvas::new(options);
+    let mut summaries: Vec<(String, Summary)> = groups
+        .iter()
+        .filter(|(_, values)| !values.is_empty())
+        .map(|(label, values)| (label.clone(), Summary::new(values)))
+        .collect();
+    if summaries.is_empty() {
+        return canvas.no_data();
+    }
+    sort_by(&mut summaries, options.sort, |summary| summary.median);
+
+    let left = label_margin(
+        summaries.iter().map(|(label, _)| label.as_str()),
+        canvas.width,
+    );
+    let (top, bottom, right) = (top(options), canvas.height - 24.0, canvas.width - 16.0);
+    let values = groups.iter().flat_map(|(_, values)| values.iter().copied());
+    let low = values.clone().fold(f64::INFINITY, f64::min);
+    let high = values.fold(f64::NEG_INFINITY, f64::max);
+    let ticks = ticks(low, high, 5);
+    let (start, end) = (ticks[0], ticks[ticks.len() - 1]);
+    let x = |value: f64| left + (value - start) / (end - start) * (right - left);
+    x_axis(&mut canvas, &ticks, x, top, bottom);
+
+    let band = (bottom - top) / summaries.len() as f64;
+    for (i, (label, summary)) in summaries.iter().enumerate() {
+        let middle = top + (i as f64 + 0.5) * band;
+        let half = band * 0.3;
+        canvas.line(x(summary.low), middle, x(summary.q1), middle, "#333");
+        canvas.line(x(summary.q3), middle, x(summary.high), middle, "#333");
+        for whisker in [summary.low, summary.high] {
+            canvas.line(
+                x(whisker),
+                middle - half / 2.0,
+                x(whisker),
+                middle + half / 2.0,
+                "#333",
+            );
+        }
+        canvas.rect(
+            x(summary.q1),
+            middle - half,
+            x(summary.q3) - x(summary.q1),
+            2.0 * half,
+            &format!(
+                "{}: median {}, quartiles {}–{}",
+                label,
+                short(summary.median),
+                short(summary.q1),
+                short(summary.q3)
+            ),
+        );
+        canvas.line(
+            x(summary.median),
+            middle - half,
+            x(summary.median),
+            middle + half,
+            "white",
+        );
+        for outlier in &summary.outliers {
+            let _ = writeln!(
+                canvas.svg,
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"2.5\" fill=\"none\" stroke=\"{}\"><title>{}</title></circle>",
+                x(*outlier),
+                middle,
+                COLOR,
+                outlier
+            );
+        }
+        canvas.text(left - 6.0, middle + 4.0, "end", label);
+    }
+    canvas.finish()
+}
+
+pub fn histogram(values: &[f64], options: &ChartOptions) -> String {
+    let mut canvas = Canvas::new(options);
+    if values.is_empty() {
+        return canvas.no_data();
+    }
+    let bins = options.bins.clamp(1, 200);
+    let low = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let width = if high > low {
+        (high - low) / bins as f64
+    } else {
+        1.0
+    };
+    let mut counts = vec![0usize; bins];
+    for value in values {
+        let bin = ((value - low) / width) as usize;
+        counts[bin.min(bins - 1)] += 1;
+    }
+
+    // Counts are whole numbers, so skip fractional ticks.
+    let y_ticks: Vec<f64> = ticks(0.0, *counts.iter().max().unwrap() as f64, 5)
+        .into_iter()
+        .filter(|tick| tick.fract() == 0.0)
+        .collect();
+    let tick_labels: Vec<String> = y_ticks.iter().map(|tick| short(*tick)).collect();
+    let left = label_margin(tick_labels.iter().map(String::as_str), canvas.width);
+    let (top, bottom, right) = (top(options), canvas.height - 24.0, canvas.width - 16.0);
+    let y_end = y_ticks[y_ticks.len() - 1];
+    let y = |count: f64| bottom - count / y_end * (bottom - top);
+    for tick in &y_ticks {
+        canvas.line(left, y(*tick), right, y(*tick), "#ddd");
+        canvas.text(left - 6.0, y(*tick) + 4.0, "end", &short(*tick));
+    }
+
+    let high = low + width * bins as f64;
+    let x = |value: f64| left + (value - low) / (high - low) * (right - left);
+    for (i, count) in counts.iter().enumerate() {
+        let (from, to) = (low + i as f64 * width, low + (i + 1) as f64 * width);
+        canvas.rect(
+            x(from) + 0.5,
+            y(*count as f64),
+            x(to) - x(from) - 1.0,
+            bottom - y(*count as f64),
+            &format!("{}–{}: {}", short(from), short(to), count),
+        );
+    }
+    for tick in ticks(low, high, 5) {
+        if tick >= low && tick <= high {
+            canvas.text(x(tick), bottom + 14.0, "middle", &short(tick));
+        }
+    }
+    canvas.line(left, bottom, right, bottom, "#333");
+    canvas.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(sort: Sort) -> ChartOptions {
+        ChartOptions {
+            sort,
+            ..ChartOptions::default()
+        }
+    }
+
+    // The tooltips of the bars, boxes or bins, top to bottom.
+    fn tooltips(svg: &str) -> Vec<&str> {
+        svg.split("<title>")
+            .skip(1)
+            .map(|rest| &rest[..rest.find("</title>").unwrap()])
+            .collect()
+    }
+
+    fn labels(tooltips: Vec<&str>) -> Vec<&str> {
+        tooltips
+            .into_iter()
+            .map(|tooltip| &tooltip[..tooltip.find(':').unwrap()])
+            .collect()
+    }
+
+    fn pairs(pairs: &[(&str, f64)]) -> Vec<(String, f64)> {
+        pairs
+            .iter()
+            .map(|&(label, value)| (label.to_string(), value))
+            .collect()
+    }
+
+    #[test]
+    fn empty_data_draws_no_data() {
+        let options = ChartOptions {
+            title: Some("Payroll".to_string()),
+            ..ChartOptions::default()
+        };
+        let charts = [
+            bar_chart(&[], &options),
+            box_plot(&[("A".to_string(), Vec::new())], &options),
+            histogram(&[], &options),
+        ];
+        for svg in charts {
+            assert!(svg.starts_with("<svg "), "{}", svg);
+            assert!(svg.ends_with("</svg>\n"), "{}", svg);
+            assert!(svg.contains(">Payroll</text>"), "{}", svg);
+            assert!(
+                svg.contains("<text x=\"320.0\" y=\"200.0\" text-anchor=\"middle\">No data</text>"),
+                "{}",
+                svg
+            );
+            // Nothing but the background is drawn.
+            assert!(tooltips(&svg).is_empty(), "{}", svg);
+            assert!(!svg.contains("<line"), "{}", svg);
+        }
+    }
+
+    #[test]
+    fn team_names_and_titles_are_escaped() {
+        let options = ChartOptions {
+            title: Some("\"Fire\" & <Ice>".to_string()),
+            ..ChartOptions::default()
+        };
+        let team = "Fire & <Ice>";
+        let charts = [
+            bar_chart(&pairs(&[(team, 3.0)]), &options),
+            box_plot(&[(team.to_string(), vec![1.0, 2.0, 3.0])], &options),
+        ];
+        for svg in charts {
+            assert!(svg.contains(">&quot;Fire&quot; &amp; &lt;Ice&gt;</text>"));
+            // Both the axis label and the tooltip.
+            assert_eq!(svg.matches("Fire &amp; &lt;Ice&gt;").count(), 2, "{}", svg);
+            assert!(!svg.contains("<Ice>"), "{}", svg);
+        }
+    }
+
+    #[test]
+    fn bars_follow_the_sort() {
+        let data = pairs(&[("B", 1.0), ("A", 3.0), ("C", 2.0)]);
+        for (sort, expected) in [
+            (Sort::Desc, ["A", "C", "B"]),
+            (Sort::Asc, ["B", "C", "A"]),
+            (Sort::Label, ["A", "B", "C"]),
+            (Sort::None, ["B", "A", "C"]),
+        ] {
+            let svg = bar_chart(&data, &options(sort));
+            assert_eq!(labels(tooltips(&svg)), expected, "{:?}", sort);
+        }
+        assert_eq!(
+            tooltips(&bar_chart(&data, &ChartOptions::default())),
+            ["A: 3", "C: 2", "B: 1"]
+        );
+    }
+
+    #[test]
+    fn boxes_sort_by_median() {
+        // C has the widest range but the middle median.
+        let groups = vec![
+            ("B".to_string(), vec![1.0, 2.0, 3.0]),
+            ("A".to_string(), vec![5.0, 6.0, 7.0]),
+            ("C".to_string(), vec![0.0, 4.0, 8.0]),
+        ];
+        for (sort, expected) in [
+            (Sort::Desc, ["A", "C", "B"]),
+            (Sort::Asc, ["B", "C", "A"]),
+            (Sort::Label, ["A", "B", "C"]),
+            (Sort::None, ["B", "A", "C"]),
+        ] {
+            let svg = box_plot(&groups, &options(sort));
+            assert_eq!(labels(tooltips(&svg)), expected, "{:?}", sort);
+        }
+        let svg = box_plot(&groups, &options(Sort::Desc));
+        assert_eq!(tooltips(&svg)[0], "A: median 6, quartiles 5.5–6.5");
+    }
+
+    #[test]
+    fn histogram_bins_stay_in_value_order() {
+        let values = [10.0, 0.0, 0.0, 0.0];
+        for sort in [Sort::Desc, Sort::Asc, Sort::Label, Sort::None] {
+            let options = ChartOptions {
+                bins: 2,
+                ..options(sort)
+            };
+            let svg = histogram(&values, &options);
+            assert_eq!(tooltips(&svg), ["0–5: 3", "5–10: 1"], "{:?}", sort);
+        }
+    }
+}
@@ -0,0 +1,356 @@
+// Cross-origin access for browser clients.
+//
+// CORS is off unless CORS_ALLOWED_ORIGINS is set, to "*" or to a comma
+// separated list of origins such as "https://app.example.com". Origins are
+// matched exactly. The rest is optional:
+//
+//   CORS_ALLOWED_METHODS   default "GET,HEAD,POST"
+//   CORS_ALLOWED_HEADERS   default "content-type,authorization,x-api-key,if-none-match"
+//   CORS_MAX_AGE           seconds browsers may cache a preflight, default 600
+//
+// `cors` answers preflights itself, so they never reach the API key check
+// (browsers don't send keys on preflights). Answers to allowed origins,
+// errors included, let the page read the ETag, rate limit and download
+// headers.
+
+use axum::{
+    extract::State,
+    http::{header, HeaderName, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use polars::prelude::PolarsError;
+use std::sync::Arc;
+
+pub const DEFAULT_METHODS: &str = "GET,HEAD,POST";
+pub const DEFAULT_HEADERS: &str = "content-type,authorization,x-api-key,if-none-match";
+pub const DEFAULT_MAX_AGE: u64 = 600;
+
+// Response headers besides the CORS-safelisted ones that pages may read.
+const EXPOSED: &str =
+    "etag,retry-after,x-ratelimit-remaining,x-quota-remaining,content-disposition";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Origins {
+    Any,
+    List(Vec<HeaderValue>),
+}
+
+#[derive(Debug, Clone)]
+pub struct CorsPolicy {
+    origins: Origins,
+    methods: HeaderValue,
+    headers: HeaderValue,
+    max_age: HeaderValue,
+}
+
+fn invalid(err: impl ToString) -> PolarsError {
+    PolarsError::ComputeError(format!("invalid CORS configuration: {}", err.to_string()).into())
+}
+
+// The non-empty items of a comma separated list.
+fn items(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+impl CorsPolicy {
+    pub fn new(origins: Origins, methods: &[Method], headers: &[HeaderName], max_age: u64) -> Self {
+        let join = |items: Vec<&str>| HeaderValue::from_str(&items.join(",")).unwrap();
+        CorsPolicy {
+            origins,
+            methods: join(methods.iter().map(Method::as_str).collect()),
+            headers: join(headers.iter().map(HeaderName::as_str).collect()),
+            max_age: HeaderValue::from(max_age),
+        }
+    }
+
+    // The policy from the CORS_* variables as `var` returns them, None when
+    // CORS_ALLOWED_ORIGINS is unset.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Option<Self>, PolarsError> {
+        let Some(origins) = var("CORS_ALLOWED_ORIGINS") else {
+            return Ok(None);
+        };
+        let origins = if origins.trim() == "*" {
+            Origins::Any
+        } else {
+            let origins = items(&origins)
+                .map(|origin| HeaderValue::from_str(origin).map_err(invalid))
+                .collect::<Result<Vec<_>, _>>()?;
+            if origins.is_empty() {
+                return Err(invalid("CORS_ALLOWED_ORIGINS names no origin"));
+            }
+            Origins::List(origins)
+        };
+        let methods = var("CORS_ALLOWED_METHODS").unwrap_or_else(|| DEFAULT_METHODS.to_string());
+        let methods = items(&methods)
+            .map(|method| Method::from_bytes(method.to_uppercase().as_bytes()).map_err(invalid))
+            .collect::<Result<Vec<_>, _>>()?;
+        let headers = var("CORS_ALLOWED_HEADERS").unwrap_or_else(|| DEFAULT_HEADERS.to_string());
+        let headers = items(&headers)
+            .map(|name| HeaderName::from_bytes(name.as_bytes()).map_err(invalid))
+            .collect::<Result<Vec<_>, _>>()?;
+        let max_age = match var("CORS_MAX_AGE") {
+            Some(seconds) => seconds
+                .trim()
+                .parse()
+                .map_err(|_| invalid(format!("CORS_MAX_AGE {:?} is not seconds", seconds)))?,
+            None => DEFAULT_MAX_AGE,
+        };
+        Ok(Some(CorsPolicy::new(origins, &methods, &headers, max_age)))
+    }
+
+    pub fn from_env() -> Result<Option<Self>, PolarsError> {
+        CorsPolicy::from_vars(|name| std::env::var(name).ok())
+    }
+
+    // The Access-Control-Allow-Origin value for a request from `origin`,
+    // None when it isn't allowed.
+    fn allow_origin(&self, origin: &HeaderValue) -> Option<HeaderValue> {
+        match &self.origins {
+            Origins::Any => Some(HeaderValue::from_static("*")),
+            Origins::List(origins) => origins.contains(origin).then(|| origin.clone()),
+        }
+    }
+}
+
+// Answers preflights and labels answers to allowed origins.
+pub async fn cors<B>(
+    State(policy): State<Arc<CorsPolicy>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let origin = request.headers().get(header::ORIGIN).cloned();
+    let preflight = origin.is_some()
+        && request.method() == Method::OPTIONS
+        && request
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+
+    let mut response = if preflight {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        next.run(request).await
+    };
+    let headers = response.headers_mut();
+    // Answers differ by origin, also for requests without one, so caches
+    // must keep them apart.
+    if policy.origins != Origins::Any {
+        headers.append(header::VARY, HeaderValue::from_static("origin"));
+    }
+    let Some(allow_origin) = origin.and_then(|origin| policy.allow_origin(&origin)) else {
+        // Without the headers the browser refuses the answer.
+        return response;
+    };
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+    if preflight {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_METHODS, policy.methods.clone());
+        headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, policy.headers.clone());
+        headers.insert(header::ACCESS_CONTROL_MAX_AGE, policy.max_age.clone());
+    } else {
+        headers.insert(
+            header::ACCESS_CONTROL_EXPOSE_HEADERS,
+            HeaderValue::from_static(EXPOSED),
+        );
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{app, protect};
+    use crate::auth::KeyStore;
+    use crate::currency::RateTable;
+    use axum::{middleware, Router};
+    use lambda_http::Body;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use tower::ServiceExt;
+
+    const APP: &str = "https://app.example.com";
+
+    fn policy(vars: &[(&str, &str)]) -> Result<Option<CorsPolicy>, PolarsError> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        CorsPolicy::from_vars(|name| vars.get(name).map(|value| value.to_string()))
+    }
+
+    // The app as main.rs assembles it, behind a key, with CORS for APP.
+    fn router() -> Router<(), Body> {
+        let keys = KeyStore::from_json(r#"[{"key": "s3cret", "name": "web"}]"#).unwrap();
+        let policy = policy(&[("CORS_ALLOWED_ORIGINS", APP), ("CORS_MAX_AGE", "3600")])
+            .unwrap()
+            .unwrap();
+        protect(app(RateTable::default()), keys)
+            .layer(middleware::from_fn_with_state(Arc::new(policy), cors))
+    }
+
+    // A Lambda function URL event, as the runtime hands it to the router.
+    fn event(method: &str, path: &str, headers: &[(&str, &str)]) -> lambda_http::Request {
+        let mut all: HashMap<&str, &str> = headers.iter().copied().collect();
+        all.insert("host", "id.lambda-url.eu-west-2.on.aws");
+        let event = json!({
+            "version": "2.0",
+            "routeKey": "$default",
+            "rawPath": path,
+            "rawQueryString": "",
+            "headers": all,
+            "requestContext": {
+                "accountId": "123456789012",
+                "apiId": "id",
+                "domainName": "id.lambda-url.eu-west-2.on.aws",
+                "domainPrefix": "id",
+                "http": {
+                    "method": method,
+                    "path": path,
+                    "protocol": "HTTP/1.1",
+                    "sourceIp": "65.78.31.245",
+                    "userAgent": "Mozilla/5.0"
+                },
+                "requestId": "MIZRNhJtIAMEMDw=",
+                "routeKey": "$default",
+                "stage": "$default",
+                "time": "11/Jan/2023:11:45:34 +0000",
+                "timeEpoch": 1673437534837u64
+            },
+            "isBase64Encoded": false
+        });
+        lambda_http::request::from_str(&event.to_string()).unwrap()
+    }
+
+    async fn send(request: lambda_http::Request) -> Response {
+        router().oneshot(request).await.unwrap()
+    }
+
+    fn header(response: &Response, name: HeaderName) -> Option<&str> {
+        response
+            .headers()
+            .get(name)
+            .map(|value| value.to_str().unwrap())
+    }
+
+    #[test]
+    fn unset_origins_turn_cors_off() {
+        assert!(policy(&[("CORS_MAX_AGE", "60")]).unwrap().is_none());
+    }
+
+    #[test]
+    fn bad_configuration_is_an_error() {
+        for vars in [
+            [("CORS_ALLOWED_ORIGINS", " , "), ("CORS_MAX_AGE", "60")],
+            [("CORS_ALLOWED_ORIGINS", APP), ("CORS_MAX_AGE", "an hour")],
+            [
+                ("CORS_ALLOWED_ORIGINS", APP),
+                ("CORS_ALLOWED_HEADERS", "x api key"),
+            ],
+            [
+                ("CORS_ALLOWED_ORIGINS", APP),
+                ("CORS_ALLOWED_METHODS", "GET,P(ST"),
+            ],
+        ] {
+            assert!(policy(&vars).is_err(), "{:?}", vars);
+        }
+    }
+
+    #[tokio::test]
+    async fn preflights_are_answered_before_the_key_check() {
+        let response = send(event(
+            "OPTIONS",
+            "/leagues/mls/salaries/players",
+            &[
+                ("origin", APP),
+                ("access-control-request-method", "GET"),
+                ("access-control-request-headers", "authorization"),
+            ],
+        ))
+        .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            header(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(APP)
+        );
+        assert_eq!(
+            header(&response, header::ACCESS_CONTROL_ALLOW_METHODS),
+            Some(DEFAULT_METHODS)
+        );
+        assert_eq!(
+            header(&response, header::ACCESS_CONTROL_ALLOW_HEADERS),
+            Some(DEFAULT_HEADERS)
+        );
+        // Keys may come as `Authorization: Bearer` as well as `X-API-Key`.
+        assert!(DEFAULT_HEADERS
+            .split(',')
+            .any(|name| name == "authorization"));
+        assert_eq!(
+            header(&response, header::ACCESS_CONTROL_MAX_AGE),
+            Some("3600")
+        );
+        assert_eq!(header(&response, header::VARY), Some("origin"));
+    }
+
+    #[tokio::test]
+    async fn preflights_from_other_origins_are_not_allowed() {
+        let response = send(event(
+            "OPTIONS",
+            "/salaries/players",
+            &[
+                ("origin", "https://evil.example.com"),
+                ("access-control-request-method", "GET"),
+            ],
+        ))
+        .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(header(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN), None);
+        assert_eq!(
+            header(&response, header::ACCESS_CONTROL_ALLOW_METHODS),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn answers_to_allowed_origins_are_readable() {
+        let response = send(event(
+            "GET",
+            "/salaries/top/team/3",
+            &[("origin", APP), ("x-api-key", "s3cret")],
+        ))
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            header(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(APP)
+        );
+        assert_eq!(
+            header(&response, header::ACCESS_CONTROL_EXPOSE_HEADERS),
+            Some(EXPOSED)
+        );
+        assert!(header(&response, header::ETAG).is_some());
+
+        // So the page can tell the user why.
+        let rejected = send(event("GET", "/salaries/top/team/3", &[("origin", APP)])).await;
+        assert_eq!(rejected.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            header(&rejected, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(APP)
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_without_an_origin_get_no_cors_headers() {
+        let response = send(event(
+            "GET",
+            "/salaries/top/team/3",
+            &[("x-api-key", "s3cret")],
+        ))
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(header(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN), None);
+        let vary: Vec<_> = response.headers().get_all(header::VARY).iter().collect();
+        assert!(
+            vary.contains(&&HeaderValue::from_static("origin")),
+            "{:?}",
+            vary
+        );
+    }
+}
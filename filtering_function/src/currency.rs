@@ -0,0 +1,425 @@
+// Currency conversion and display formatting for salary figures.
+//
+// Salaries are stored in each league's own currency. A `Money` setting
+// converts them with a static `RateTable` (built in, or loaded from a JSON
+// file) and optionally renders them as text, either in full
+// ("$1,250,000.00") or compact ("$1.25M") form, with the thousands and
+// decimal separators of a locale.
+//
+// Only amounts in responses are converted. Amounts a request sends, such as
+// thresholds, filter values, budgets and band edges, are always read in the
+// league's currency.
+
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use utoipa::ToSchema;
+
+// Fields and columns holding an amount of money, converted wherever they
+// appear in a response.
+pub const MONEY_FIELDS: &[&str] = &[
+    "salary",
+    "payroll",
+    "total_salary",
+    "nth_salary",
+    "threshold",
+    "budget_charge",
+    "headroom",
+    "tam_required",
+    "center",
+    "spread",
+    "lower_fence",
+    "upper_fence",
+    "cost_per_goal",
+    "cost_per_contribution",
+    "cost_per_90",
+];
+
+// Units of each currency per one unit of `base`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RateTable {
+    pub base: String,
+    pub rates: HashMap<String, f64>,
+}
+
+impl Default for RateTable {
+    fn default() -> Self {
+        let rates = [
+            ("USD", 1.0),
+            ("EUR", 0.92),
+            ("GBP", 0.79),
+            ("CAD", 1.35),
+            ("MXN", 17.10),
+        ];
+        RateTable {
+            base: "USD".to_string(),
+            rates: rates
+                .into_iter()
+                .map(|(code, rate)| (code.to_string(), rate))
+                .collect(),
+        }
+    }
+}
+
+impl RateTable {
+    // Parses a table like `{"base": "USD", "rates": {"EUR": 0.92}}`. Rates
+    // must be finite and positive, or conversions would give inf or NaN.
+    pub fn from_json(text: &str) -> Result<Self, PolarsError> {
+        let invalid =
+            |err: String| PolarsError::ComputeError(format!("invalid rate table: {}", err).into());
+        let table: RateTable =
+            serde_json::from_str(text).map_err(|err| invalid(err.to_string()))?;
+        let mut rates: Vec<_> = table.rates.iter().collect();
+        rates.sort_by(|a, b| a.0.cmp(b.0));
+        if let Some((code, rate)) = rates
+            .into_iter()
+            .find(|(_, rate)| !rate.is_finite() || **rate <= 0.0)
+        {
+            return Err(invalid(format!(
+                "{} rate {} is not a positive number",
+                code, rate
+            )));
+        }
+        Ok(table)
+    }
+
+    // `from_json` on the contents of `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, PolarsError> {
+        RateTable::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    fn rate(&self, code: &str) -> Option<f64> {
+        if code.eq_ignore_ascii_case(&self.base) {
+            return Some(1.0);
+        }
+        self.rates
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(code))
+            .map(|(_, rate)| *rate)
+    }
+
+    // Multiplier turning an amount in `from` into `to`.
+    pub fn conversion(&self, from: &str, to: &str) -> Result<f64, PolarsError> {
+        let rate = |code: &str| {
+            self.rate(code).ok_or_else(|| {
+                PolarsError::ComputeError(format!("unknown currency {}", code).into())
+            })
+        };
+        Ok(rate(to)? / rate(from)?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Compact,
+    Full,
+}
+
+// Separators and symbol placement of a locale.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "en-US", alias = "en-GB", alias = "en")]
+    English,
+    #[serde(rename = "de-DE", alias = "es-ES", alias = "de", alias = "es")]
+    German,
+    #[serde(rename = "fr-FR", alias = "fr")]
+    French,
+}
+
+impl Locale {
+    fn separators(self) -> (&'static str, &'static str) {
+        match self {
+            Locale::English => (",", "."),
+            Locale::German => (".", ","),
+            // Narrow no-break space.
+            Locale::French => ("\u{202f}", ","),
+        }
+    }
+}
+
+// Display symbol of an ISO 4217 code, or the code itself.
+pub fn symbol(code: &str) -> &str {
+    match code.to_ascii_uppercase().as_str() {
+        "USD" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        "CAD" => "CA$",
+        "MXN" => "MX$",
+        _ => code,
+    }
+}
+
+// How money is presented in one response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Money {
+    pub currency: String,
+    pub factor: f64,
+    pub format: Option<Format>,
+    pub locale: Locale,
+}
+
+impl Money {
+    // Converts from `source` currency to `target` (or keeps `source`).
+    pub fn new(
+        rates: &RateTable,
+        source: &str,
+        target: Option<&str>,
+        format: Option<Format>,
+        locale: Locale,
+    ) -> Result<Self, PolarsError> {
+        let currency = target.unwrap_or(source).to_ascii_uppercase();
+        Ok(Money {
+            factor: rates.conversion(source, &currency)?,
+            currency,
+            format,
+            locale,
+        })
+    }
+
+    pub fn convert(&self, amount: f64) -> f64 {
+        amount * self.factor
+    }
+
+    // Renders an already converted amount.
+    pub fn display(&self, amount: f64, format: Format) -> String {
+        let (thousands, decimal) = self.locale.separators();
+        let number = match format {
+            Format::Full => group(amount, 2, thousands, decimal),
+            Format::Compact => {
+                let (scaled, suffix) = match amount.abs() {
+                    a if a >= 1e9 => (amount / 1e9, "B"),
+                    a if a >= 1e6 => (amount / 1e6, "M"),
+                    a if a >= 1e3 => (amount / 1e3, "k"),
+                    _ => (amount, ""),
+                };
+                let digits = group(scaled, 2, thousands, decimal);
+                let digits = digits.trim_end_matches('0').trim_end_matches(decimal);
+                format!("{}{}", digits, suffix)
+            }
+        };
+        let symbol = symbol(&self.currency);
+        match self.locale {
+            Locale::English => match number.strip_prefix('-') {
+                Some(positive) => format!("-{}{}", symbol, positive),
+                None => format!("{}{}", symbol, number),
+            },
+            Locale::German | Locale::French => format!("{}\u{a0}{}", number, symbol),
+        }
+    }
+
+    fn localize_amount(&self, amount: f64) -> Value {
+        let amount = self.convert(amount);
+        match self.format {
+            Some(format) => Value::String(self.display(amount, format)),
+            None => serde_json::Number::from_f64(amount).map_or(Value::Null, Value::Number),
+        }
+    }
+
+    // Converts (and formats) every MONEY_FIELDS value in a JSON document,
+    // including arrays of amounts.
+    pub fn localize_json(&self, value: &mut Value) {
+        match value {
+            Value::Object(fields) => {
+                for (key, field) in fields.iter_mut() {
+                    if MONEY_FIELDS.contains(&key.as_str()) {
+                        self.localize_field(field);
+                    } else {
+                        self.localize_json(field);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.localize_json(item)),
+            _ => {}
+        }
+    }
+
+    fn localize_field(&self, field: &mut Value) {
+        match field {
+            Value::Number(number) => {
+                if let Some(amount) = number.as_f64() {
+                    *field = self.localize_amount(amount);
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.localize_field(item)),
+            _ => {}
+        }
+    }
+
+    // Converts (and formats) the MONEY_FIELDS columns of a DataFrame.
+    pub fn localize_frame(&self, df: &DataFrame) -> Result<DataFrame, PolarsError> {
+        let mut df = df.clone();
+        for name in MONEY_FIELDS {
+            let Ok(column) = df.column(name) else {
+                continue;
+            };
+            let converted: Series = column.cast(&DataType::Float64)? * self.factor;
+            let mut localized = match self.format {
+                Some(format) => converted
+                    .f64()?
+                    .into_iter()
+                    .map(|amount| amount.map(|amount| self.display(amount, format)))
+                    .collect::<Utf8Chunked>()
+                    .into_series(),
+                None => converted,
+            };
+            localized.rename(name);
+            df.with_column(localized)?;
+        }
+        Ok(df)
+    }
+}
+
+// Fixed-point rendering with a thousands separator.
+fn group(amount: f64, decimals: usize, thousands: &str, decimal: &str) -> String {
+    let fixed = format!("{:.*}", decimals, amount.abs());
+    let (integer, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+    let mut grouped = String::new();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push_str(thousands);
+        }
+        grouped.push(digit);
+    }
+    let sign = if amount < 0.0 && fixed.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        "-"
+    } else {
+        ""
+    };
+    if fraction.is_empty() {
+        format!("{}{}", sign, grouped)
+    } else {
+        format!("{}{}{}{}", sign, grouped, decimal, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::app;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    async fn body(uri: &str) -> String {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = app::<Body>(RateTable::default())
+            .oneshot(request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn charts_convert_amounts_but_not_thresholds() {
+        // The threshold is read in dollars either way, so the same players
+        // are counted.
+        let bar = "/salaries/charts/bar?threshold=2000000";
+        assert_eq!(
+            body(bar).await,
+            body(&format!("{}&currency=EUR", bar)).await
+        );
+        let histogram = "/salaries/charts/histogram";
+        assert_ne!(
+            body(histogram).await,
+            body(&format!("{}?currency=EUR", histogram)).await
+        );
+    }
+
+    #[test]
+    fn rates_must_be_positive_numbers() {
+        let table = RateTable::from_json(r#"{"base": "USD", "rates": {"EUR": 0.5}}"#).unwrap();
+        assert_eq!(table.conversion("EUR", "USD").unwrap(), 2.0);
+        assert!(table.conversion("USD", "XYZ").is_err());
+        for rates in [r#"{"EUR": 0}"#, r#"{"EUR": -0.92}"#, r#"{"EUR": 1e999}"#] {
+            let json = format!(r#"{{"base": "USD", "rates": {}}}"#, rates);
+            assert!(RateTable::from_json(&json).is_err(), "{}", rates);
+        }
+    }
+
+    fn money(currency: &str, locale: Locale) -> Money {
+        Money::new(&RateTable::default(), "USD", Some(currency), None, locale).unwrap()
+    }
+
+    #[test]
+    fn amounts_display_in_the_locale() {
+        let en = money("USD", Locale::English);
+        assert_eq!(en.display(1_250_000.0, Format::Full), "$1,250,000.00");
+        assert_eq!(en.display(1_250_000.0, Format::Compact), "$1.25M");
+        assert_eq!(en.display(-1_500.0, Format::Full), "-$1,500.00");
+        assert_eq!(en.display(-1_500.0, Format::Compact), "-$1.5k");
+        assert_eq!(en.display(2_000_000_000.0, Format::Compact), "$2B");
+        assert_eq!(en.display(999.0, Format::Compact), "$999");
+
+        let de = money("EUR", Locale::German);
+        assert_eq!(de.display(1_250_000.5, Format::Full), "1.250.000,50\u{a0}€");
+        assert_eq!(de.display(1_250_000.0, Format::Compact), "1,25M\u{a0}€");
+        let fr = money("EUR", Locale::French);
+        assert_eq!(
+            fr.display(1_250_000.0, Format::Full),
+            "1\u{202f}250\u{202f}000,00\u{a0}€"
+        );
+        assert!(Money::new(
+            &RateTable::default(),
+            "USD",
+            Some("CHF"),
+            None,
+            Locale::English
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn amounts_round_before_they_are_displayed() {
+        let en = money("USD", Locale::English);
+        // Rounds to zero, so no minus sign.
+        assert_eq!(en.display(-0.004, Format::Full), "$0.00");
+        assert_eq!(en.display(-0.004, Format::Compact), "$0");
+        // Rounds up into the next thousand without moving to "M".
+        assert_eq!(en.display(999_999.995, Format::Compact), "$1,000k");
+        assert_eq!(en.display(0.126, Format::Full), "$0.13");
+    }
+
+    #[test]
+    fn group_separates_thousands() {
+        assert_eq!(group(0.0, 2, ",", "."), "0.00");
+        assert_eq!(group(999.0, 0, ",", "."), "999");
+        assert_eq!(group(1_000.0, 0, ",", "."), "1,000");
+        assert_eq!(group(123_456_789.126, 2, ".", ","), "123.456.789,13");
+        assert_eq!(group(-1_234.5, 1, " ", "."), "-1 234.5");
+        assert_eq!(group(-0.001, 2, ",", "."), "0.00");
+    }
+
+    #[test]
+    fn json_money_fields_are_localized_at_any_depth() {
+        let mut en = money("EUR", Locale::English);
+        let mut value = serde_json::json!({
+            "team": "LAFC",
+            "payroll": 1000.0,
+            "players": 3,
+            "bands": [{"payroll": [100.0, 200.0], "label": "<1k"}],
+            "nested": {"salary": 50.0, "threshold": null, "goals": 2}
+        });
+        en.localize_json(&mut value);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "team": "LAFC",
+                "payroll": 920.0,
+                "players": 3,
+                "bands": [{"payroll": [92.0, 184.0], "label": "<1k"}],
+                "nested": {"salary": 46.0, "threshold": null, "goals": 2}
+            })
+        );
+
+        en.format = Some(Format::Compact);
+        let mut value = serde_json::json!([{"salary": 1_000_000.0}]);
+        en.localize_json(&mut value);
+        assert_eq!(value, serde_json::json!([{"salary": "€920k"}]));
+    }
+}
@@ -0,0 +1,373 @@
+// Cost-efficiency metrics from external per-player stats.
+//
+// A stats CSV with `first_name,last_name,minutes,goals,assists` (and
+// optionally `team`) is matched to the salaries table by player name, then
+// every matched player gets:
+//  - cost_per_goal:         salary / goals
+//  - cost_per_contribution: salary / (goals + assists)
+//  - cost_per_90:           salary / (minutes / 90)
+// and their rank on each metric (1 = cheapest) within their team and
+// position. Metrics are null when the divisor is zero.
+//
+// Names are compared after `normalize_name`; a stats row without an exact
+// match is paired with the most similar salaries name if the similarity
+// reaches `min_similarity`. When the stats carry a team, it is used to pick
+// between players sharing a name.
+//
+// Fuzzy matching compares every stats name with every salaries name, so
+// uploads are held to MAX_STATS_ROWS rows and names of MAX_NAME_CHARS.
+
+use crate::names::{normalize_name, similarity};
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+pub const DEFAULT_MIN_SIMILARITY: f64 = 0.85;
+
+// Limits of an uploaded stats CSV, a season of a whole league with room to
+// spare.
+pub const MAX_STATS_ROWS: usize = 2_000;
+pub const MAX_NAME_CHARS: usize = 100;
+
+const METRICS: [&str; 3] = ["cost_per_goal", "cost_per_contribution", "cost_per_90"];
+
+pub struct Efficiency {
+    // Salaries columns, the stats, `match_score` and the metrics and ranks.
+    pub table: DataFrame,
+    // "first_name last_name" of the stats rows that matched no player.
+    pub unmatched: Vec<String>,
+}
+
+// Parse a stats CSV uploaded as text, within MAX_STATS_ROWS and
+// MAX_NAME_CHARS.
+pub fn read_stats(csv: &str) -> Result<DataFrame, PolarsError> {
+    // Count lines first so an oversized upload isn't parsed at all.
+    let rows = csv.lines().skip(1).filter(|line| !line.is_empty()).count();
+    if rows > MAX_STATS_ROWS {
+        return Err(PolarsError::ComputeError(
+            format!(
+                "stats have {} rows, at most {} are allowed",
+                rows, MAX_STATS_ROWS
+            )
+            .into(),
+        ));
+    }
+    let stats = CsvReader::new(Cursor::new(csv)).has_header(true).finish()?;
+    let names = [stats.column("first_name")?, stats.column("last_name")?];
+    for name in names {
+        let longest = name
+            .utf8()?
+            .into_iter()
+            .flatten()
+            .map(|name| name.chars().count())
+            .max();
+        if longest.is_some_and(|longest| longest > MAX_NAME_CHARS) {
+            return Err(PolarsError::ComputeError(
+                format!("stats names are limited to {} characters", MAX_NAME_CHARS).into(),
+            ));
+        }
+    }
+    Ok(stats)
+}
+
+// Parse a stats CSV from disk.
+pub fn read_stats_file(path: impl Into<PathBuf>) -> Result<DataFrame, PolarsError> {
+    CsvReader::from_path(path)?.has_header(true).finish()
+}
+
+fn full_names(df: &DataFrame) -> Result<Vec<String>, PolarsError> {
+    let first_names = df.column("first_name")?.utf8()?;
+    let last_names = df.column("last_name")?.utf8()?;
+    Ok(first_names
+        .into_iter()
+        .zip(last_names)
+        .map(|(first, last)| {
+            normalize_name(&format!(
+                "{} {}",
+                first.unwrap_or_default(),
+                last.unwrap_or_default()
+            ))
+        })
+        .collect())
+}
+
+// Index of the salaries row each stats row belongs to, with the similarity
+// of the match.
+fn match_rows(
+    salaries: &DataFrame,
+    stats: &DataFrame,
+    min_similarity: f64,
+) -> Result<Vec<Option<(u32, f64)>>, PolarsError> {
+    let salary_names = full_names(salaries)?;
+    let salary_teams: Vec<String> = salaries
+        .column("team")?
+        .utf8()?
+        .into_iter()
+        .map(|team| normalize_name(team.unwrap_or_default()))
+        .collect();
+    let stats_teams: Option<Vec<String>> = match stats.column("team") {
+        Ok(teams) => Some(
+            teams
+                .utf8()?
+                .into_iter()
+                .map(|team| normalize_name(team.unwrap_or_default()))
+                .collect(),
+        ),
+        Err(_) => None,
+    };
+
+    let mut exact: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (row, name) in salary_names.iter().enumerate() {
+        exact.entry(name).or_default().push(row);
+    }
+
+    let matches = full_names(stats)?
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let same_team = |row: &usize| {
+                stats_teams
+                    .as_ref()
+                    .is_some_and(|teams| teams[i] == salary_teams[*row])
+            };
+            // Only fall back to fuzzy matching when no name matches exactly.
+            let scored: Vec<(usize, f64)> = match exact.get(name.as_str()) {
+                Some(rows) => rows.iter().map(|row| (*row, 1.0)).collect(),
+                None => (0..salary_names.len())
+                    .map(|row| (row, similarity(name, &salary_names[row])))
+                    .filter(|(_, score)| *score >= min_similarity)
+                    .collect(),
+            };
+            scored
+                .into_iter()
+                .max_by(|(a, a_score), (b, b_score)| {
+                    a_score
+                        .total_cmp(b_score)
+                        .then(same_team(a).cmp(&same_team(b)))
+                        // Prefer the first row on a full tie.
+                        .then(b.cmp(a))
+                })
+                .map(|(row, score)| (row as u32, score))
+        })
+        .collect();
+    Ok(matches)
+}
+
+pub fn cost_efficiency(
+    salaries: &DataFrame,
+    stats: &DataFrame,
+    min_similarity: f64,
+) -> Result<Efficiency, PolarsError> {
+    let matches = match_rows(salaries, stats, min_similarity)?;
+    let unmatched = full_names(stats)?
+        .into_iter()
+        .zip(&matches)
+        .filter(|(_, matched)| matched.is_none())
+        .map(|(name, _)| name)
+        .collect();
+
+    let mut stats = stats.select(["minutes", "goals", "assists"])?;
+    stats.with_column(Series::new(
+        "row",
+        matches
+            .iter()
+            .map(|matched| matched.map(|(row, _)| row))
+            .collect::<Vec<_>>(),
+    ))?;
+    stats.with_column(Series::new(
+        "match_score",
+        matches
+            .iter()
+            .map(|matched| matched.map(|(_, score)| score))
+            .collect::<Vec<_>>(),
+    ))?;
+
+    let per = |divisor: Expr| {
+        when(divisor.clone().gt(lit(0.0)))
+            .then(col("salary") / divisor)
+            .otherwise(lit(NULL).cast(DataType::Float64))
+    };
+    let mut ranks = Vec::new();
+    for metric in METRICS {
+        for group in ["team", "position"] {
+            // Players without a metric are ranked last and then blanked out.
+            let rank = col(metric)
+                .fill_null(lit(f64::INFINITY))
+                .rank(
+                    RankOptions {
+                        method: RankMethod::Min,
+                        descending: false,
+                    },
+                    None,
+                )
+                .over([col(group)]);
+            ranks.push(
+                when(col(metric).is_not_null())
+                    .then(rank)
+                    .otherwise(lit(NULL).cast(IDX_DTYPE))
+                    .alias(&format!("{}_{}_rank", metric, group)),
+            );
+        }
+    }
+
+    let table = salaries
+        .clone()
+        .lazy()
+        .with_row_count("row", None)
+        .inner_join(
+            stats.lazy().filter(col("row").is_not_null()),
+            col("row"),
+            col("row"),
+        )
+        .with_columns([
+            col("minutes").cast(DataType::Float64),
+            col("goals").cast(DataType::Float64),
+            col("assists").cast(DataType::Float64),
+        ])
+        .with_columns([
+            per(col("goals")).alias("cost_per_goal"),
+            per(col("goals") + col("assists")).alias("cost_per_contribution"),
+            per(col("minutes") / lit(90.0)).alias("cost_per_90"),
+        ])
+        .with_columns(ranks)
+        .drop_columns(["row"])
+        .sort("cost_per_90", SortOptions::default())
+        .collect()?;
+
+    Ok(Efficiency { table, unmatched })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn salaries() -> DataFrame {
+        df!(
+            "first_name" => ["Nicol√°s", "Luis", "Luis", "Ann"],
+            "last_name" => ["Acevedo", "Díaz", "Díaz", "Lee"],
+            "team" => ["NYCFC", "Columbus Crew", "Orlando City", "NYCFC"],
+            "position" => ["M", "F", "F", "M"],
+            "salary" => [900_000.0, 600_000.0, 1_200_000.0, 300_000.0],
+        )
+        .unwrap()
+    }
+
+    fn stats(csv: &str) -> DataFrame {
+        read_stats(csv).unwrap()
+    }
+
+    fn column(table: &DataFrame, name: &str) -> Vec<Option<f64>> {
+        let column = table
+            .column(name)
+            .unwrap()
+            .cast(&DataType::Float64)
+            .unwrap();
+        column.f64().unwrap().into_iter().collect()
+    }
+
+    #[test]
+    fn names_match_across_spellings_and_typos() {
+        let efficiency = cost_efficiency(
+            &salaries(),
+            &stats(
+                "first_name,last_name,minutes,goals,assists\n\
+                 Nicolás,Acevedo,900,2,1\n\
+                 Anne,Lee,900,1,0\n\
+                 Zed,Nobody,900,1,0\n",
+            ),
+            DEFAULT_MIN_SIMILARITY,
+        )
+        .unwrap();
+        // "ann lee" is 7/8 similar to "anne lee", above the default 0.85.
+        let names = efficiency.table.column("last_name").unwrap().clone();
+        let names: Vec<_> = names.utf8().unwrap().into_iter().flatten().collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&"Acevedo") && names.contains(&"Lee"));
+        assert_eq!(efficiency.unmatched, ["zed nobody"]);
+
+        let strict = cost_efficiency(
+            &salaries(),
+            &stats("first_name,last_name,minutes,goals,assists\nAnne,Lee,900,1,0\n"),
+            0.9,
+        )
+        .unwrap();
+        assert_eq!(strict.table.height(), 0);
+        assert_eq!(strict.unmatched, ["anne lee"]);
+    }
+
+    #[test]
+    fn teams_pick_between_namesakes() {
+        let csv = |team: &str| {
+            format!(
+                "first_name,last_name,team,minutes,goals,assists\nLuis,Diaz,{},900,3,0\n",
+                team
+            )
+        };
+        for (team, salary) in [("Orlando City", 1_200_000.0), ("Columbus Crew", 600_000.0)] {
+            let efficiency =
+                cost_efficiency(&salaries(), &stats(&csv(team)), DEFAULT_MIN_SIMILARITY).unwrap();
+            assert_eq!(column(&efficiency.table, "salary"), [Some(salary)]);
+        }
+        // Without a team the first namesake is taken.
+        let efficiency = cost_efficiency(
+            &salaries(),
+            &stats("first_name,last_name,minutes,goals,assists\nLuis,Diaz,900,3,0\n"),
+            DEFAULT_MIN_SIMILARITY,
+        )
+        .unwrap();
+        assert_eq!(column(&efficiency.table, "salary"), [Some(600_000.0)]);
+    }
+
+    #[test]
+    fn metrics_divide_salary_and_rank_within_team() {
+        let efficiency = cost_efficiency(
+            &salaries(),
+            &stats(
+                "first_name,last_name,team,minutes,goals,assists\n\
+                 Nicolas,Acevedo,NYCFC,1800,3,3\n\
+                 Ann,Lee,NYCFC,900,0,2\n",
+            ),
+            DEFAULT_MIN_SIMILARITY,
+        )
+        .unwrap();
+        let table = efficiency.table;
+        // Sorted by cost_per_90: Lee 30k, Acevedo 45k.
+        assert_eq!(
+            column(&table, "cost_per_90"),
+            [Some(30_000.0), Some(45_000.0)]
+        );
+        assert_eq!(column(&table, "cost_per_goal"), [None, Some(300_000.0)]);
+        assert_eq!(
+            column(&table, "cost_per_contribution"),
+            [Some(150_000.0), Some(150_000.0)]
+        );
+        assert_eq!(
+            column(&table, "cost_per_90_team_rank"),
+            [Some(1.0), Some(2.0)]
+        );
+        // No goals means no rank, rather than the last one.
+        assert_eq!(column(&table, "cost_per_goal_team_rank"), [None, Some(1.0)]);
+        assert_eq!(
+            column(&table, "cost_per_contribution_position_rank"),
+            [Some(1.0), Some(1.0)]
+        );
+        assert_eq!(column(&table, "match_score"), [Some(1.0), Some(1.0)]);
+    }
+
+    #[test]
+    fn uploads_are_limited() {
+        let mut csv = String::from("first_name,last_name,minutes,goals,assists\n");
+        for i in 0..=MAX_STATS_ROWS {
+            csv.push_str(&format!("P{},Q,90,0,0\n", i));
+        }
+        assert!(read_stats(&csv).is_err());
+
+        let long = "x".repeat(MAX_NAME_CHARS + 1);
+        let csv = format!(
+            "first_name,last_name,minutes,goals,assists\nA,{},90,0,0\n",
+            long
+        );
+        assert!(read_stats(&csv).is_err());
+    }
+}
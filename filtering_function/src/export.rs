@@ -0,0 +1,295 @@
+// Excel workbook export of the salaries table.
+//
+// One workbook with four sheets:
+//  - Players:        the raw table, one row per player
+//  - Above threshold: players earning more than the threshold per team, as
+//                    `calculate` counts them
+//  - Payroll:        players, total, average, median and top salary per team
+//  - By position:    payroll per team (rows) and position (columns)
+// Money columns carry a currency number format so they stay numeric for
+// finance's own formulas.
+
+use crate::count_above;
+use crate::currency::symbol;
+use crate::names::repair_mac_roman;
+use polars::prelude::*;
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+
+pub const CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+fn xlsx_error(err: XlsxError) -> PolarsError {
+    PolarsError::ComputeError(format!("cannot write workbook: {}", err).into())
+}
+
+struct Formats {
+    header: Format,
+    money: Format,
+}
+
+// Writes `df` with a bold header row; `money` names the columns shown in the
+// currency format.
+fn write_frame(
+    sheet: &mut Worksheet,
+    df: &DataFrame,
+    money: &[&str],
+    formats: &Formats,
+) -> Result<(), PolarsError> {
+    for (column, series) in df.get_columns().iter().enumerate() {
+        let column = column as u16;
+        sheet
+            .write_string_with_format(0, column, series.name(), &formats.header)
+            .map_err(xlsx_error)?;
+        if let Ok(text) = series.utf8() {
+            for (row, value) in text.into_iter().enumerate() {
+                if let Some(value) = value {
+                    sheet
+                        .write_string(row as u32 + 1, column, repair_mac_roman(value))
+                        .map_err(xlsx_error)?;
+                }
+            }
+            continue;
+        }
+        let numbers = series.cast(&DataType::Float64)?;
+        for (row, value) in numbers.f64()?.into_iter().enumerate() {
+            let Some(value) = value else {
+                continue;
+            };
+            let row = row as u32 + 1;
+            if money.contains(&series.name()) {
+                sheet.write_number_with_format(row, column, value, &formats.money)
+            } else {
+                sheet.write_number(row, column, value)
+            }
+            .map_err(xlsx_error)?;
+        }
+    }
+    sheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+    sheet.autofit();
+    // Autofit measures the raw numbers, not the formatted amounts.
+    for (column, series) in df.get_columns().iter().enumerate() {
+        if money.contains(&series.name()) {
+            sheet
+                .set_column_width(column as u16, 14)
+                .map_err(xlsx_error)?;
+        }
+    }
+    Ok(())
+}
+
+fn payroll_summary(df: &DataFrame) -> Result<DataFrame, PolarsError> {
+    df.clone()
+        .lazy()
+        .groupby([col("team")])
+        .agg([
+            col("salary").count().alias("players"),
+            col("salary").sum().alias("payroll"),
+            col("salary").mean().alias("average_salary"),
+            col("salary").median().alias("median_salary"),
+            col("salary").max().alias("max_salary"),
+        ])
+        .sort(
+            "payroll",
+            SortOptions {
+                descending: true,
+                ..Default::default()
+            },
+        )
+        .collect()
+}
+
+// Payroll per team and position, with a `total` column.
+fn position_pivot(df: &DataFrame) -> Result<(DataFrame, Vec<String>), PolarsError> {
+    let mut positions: Vec<String> = df
+        .column("position")?
+        .unique_stable()?
+        .utf8()?
+        .into_iter()
+        .flatten()
+        .map(str::to_string)
+        .collect();
+    positions.sort();
+    let mut columns: Vec<Expr> = positions
+        .iter()
+        .map(|position| {
+            col("salary")
+                .filter(col("position").eq(lit(position.as_str())))
+                .sum()
+                .alias(position)
+        })
+        .collect();
+    columns.push(col("salary").sum().alias("total"));
+    let pivot = df
+        .clone()
+        .lazy()
+        .groupby([col("team")])
+        .agg(columns)
+        .sort("team", SortOptions::default())
+        .collect()?;
+    positions.push("total".to_string());
+    Ok((pivot, positions))
+}
+
+// Builds the workbook for `df`, whose salaries are in the `currency` ISO
+// 4217 code, and returns the XLSX bytes.
+pub fn salary_workbook(
+    df: &DataFrame,
+    threshold: f64,
+    currency: &str,
+) -> Result<Vec<u8>, PolarsError> {
+    let formats = Formats {
+        header: Format::new().set_bold(),
+        money: Format::new().set_num_format(format!("[${}]#,##0", symbol(currency))),
+    };
+    let mut workbook = Workbook::new();
+
+    let players = workbook
+        .add_worksheet()
+        .set_name("Players")
+        .map_err(xlsx_error)?;
+    write_frame(players, df, &["salary"], &formats)?;
+
+    let counts = count_above(df, threshold)?
+        .sort(["position", "team"], vec![true, false], false)?
+        .lazy()
+        .select([col("team"), col("position").alias("players_above")])
+        .collect()?;
+    let above = workbook
+        .add_worksheet()
+        .set_name("Above threshold")
+        .map_err(xlsx_error)?;
+    write_frame(above, &counts, &[], &formats)?;
+    // The threshold goes beside the table so the sheet explains itself.
+    let column = counts.width() as u16 + 1;
+    above
+        .write_string_with_format(0, column, "threshold", &formats.header)
+        .and_then(|sheet| sheet.write_number_with_format(1, column, threshold, &formats.money))
+        .and_then(|sheet| sheet.set_column_width(column, 14))
+        .map_err(xlsx_error)?;
+
+    let payroll = workbook
+        .add_worksheet()
+        .set_name("Payroll")
+        .map_err(xlsx_error)?;
+    write_frame(
+        payroll,
+        &payroll_summary(df)?,
+        &["payroll", "average_salary", "median_salary", "max_salary"],
+        &formats,
+    )?;
+
+    let (pivot, positions) = position_pivot(df)?;
+    let positions: Vec<&str> = positions.iter().map(String::as_str).collect();
+    let by_position = workbook
+        .add_worksheet()
+        .set_name("By position")
+        .map_err(xlsx_error)?;
+    write_frame(by_position, &pivot, &positions, &formats)?;
+
+    workbook.save_to_buffer().map_err(xlsx_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::app;
+    use crate::currency::RateTable;
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use std::io::{Cursor, Read};
+    use tower::ServiceExt;
+
+    type Archive = zip::ZipArchive<Cursor<Vec<u8>>>;
+
+    fn file(archive: &mut Archive, name: &str) -> String {
+        let mut text = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    }
+
+    // The text between each `open...>` and `close` in `xml`.
+    fn elements<'a>(xml: &'a str, open: &str, close: &str) -> Vec<&'a str> {
+        xml.split(open)
+            .skip(1)
+            .map(|rest| {
+                let text = &rest[rest.find('>').unwrap() + 1..];
+                &text[..text.find(close).unwrap()]
+            })
+            .collect()
+    }
+
+    // The header row of every sheet, in sheet order.
+    fn headers(archive: &mut Archive, sheets: usize) -> Vec<Vec<String>> {
+        let strings = file(archive, "xl/sharedStrings.xml");
+        let strings = elements(&strings, "<t", "</t>");
+        (1..=sheets)
+            .map(|sheet| {
+                let xml = file(archive, &format!("xl/worksheets/sheet{}.xml", sheet));
+                let first_row = elements(&xml, "<row r=\"1\"", "</row>")[0];
+                elements(first_row, "<v", "</v>")
+                    .into_iter()
+                    .map(|index| strings[index.parse::<usize>().unwrap()].to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn workbooks_have_a_sheet_per_view_with_headers() {
+        let request = Request::builder()
+            .uri("/salaries/export.xlsx?threshold=750000")
+            .body(Body::empty())
+            .unwrap();
+        let response = app::<Body>(RateTable::default())
+            .oneshot(request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"mls-salaries.xlsx\""
+        );
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes.to_vec())).unwrap();
+
+        let workbook = file(&mut archive, "xl/workbook.xml");
+        let names: Vec<_> = workbook
+            .split("<sheet name=\"")
+            .skip(1)
+            .map(|rest| &rest[..rest.find('"').unwrap()])
+            .collect();
+        assert_eq!(
+            names,
+            ["Players", "Above threshold", "Payroll", "By position"]
+        );
+
+        let headers = headers(&mut archive, 4);
+        assert_eq!(
+            headers[0],
+            [
+                "first_name",
+                "last_name",
+                "team",
+                "position",
+                "salary",
+                "league"
+            ]
+        );
+        assert_eq!(headers[1], ["team", "players_above", "threshold"]);
+        assert_eq!(
+            headers[2],
+            [
+                "team",
+                "players",
+                "payroll",
+                "average_salary",
+                "median_salary",
+                "max_salary"
+            ]
+        );
+        assert_eq!(headers[3].first().map(String::as_str), Some("team"));
+        assert_eq!(headers[3].last().map(String::as_str), Some("total"));
+    }
+}
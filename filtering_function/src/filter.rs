@@ -0,0 +1,997 @@
+// A small filter language over the salaries table, compiled to a Polars
+// expression.
+//
+//   (position in ["D", "D-M"] and salary > 500000) or team == "LAFC"
+//
+// Grammar, loosest binding first:
+//   expr       := and ("or" and)*
+//   and        := unary ("and" unary)*
+//   unary      := "not" unary | "(" expr ")" | comparison
+//   comparison := column op value | column ["not"] "in" "[" value ("," value)* "]"
+//   op         := "==" | "!=" | ">" | ">=" | "<" | "<="
+//   value      := number | "text" (with \" and \\ escapes)
+// `&&`, `||` and `!` may be used for and, or and not.
+//
+// Filters nest at most MAX_DEPTH parentheses and `not`s deep and hold at
+// most MAX_COMPARISONS comparisons, so a hostile filter can't exhaust the
+// stack of the parser or of the code walking its tree.
+//
+// `parse` builds the syntax tree, `Filter::check` type checks it against a
+// DataFrame schema (known columns, numbers against numeric columns, text
+// against text columns) and `Filter::to_expr` compiles it. Every error
+// carries the byte range of the offending token; `FilterError::render`
+// underlines it in the source.
+
+use crate::names::similarity;
+use polars::prelude::*;
+use std::fmt;
+
+pub const MAX_DEPTH: usize = 64;
+pub const MAX_COMPARISONS: usize = 256;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterError {
+    pub message: String,
+    // Byte range of the offending token in the filter source.
+    pub start: usize,
+    pub end: usize,
+}
+
+impl FilterError {
+    fn new(message: impl Into<String>, start: usize, end: usize) -> Self {
+        FilterError {
+            message: message.into(),
+            start,
+            end: end.max(start + 1),
+        }
+    }
+
+    // The message, the source and a caret line under the offending token:
+    //
+    //   unknown column `salry`, did you mean `salary`?
+    //     salry > 500000
+    //     ^^^^^
+    pub fn render(&self, source: &str) -> String {
+        let start = source[..self.start.min(source.len())].chars().count();
+        let width = source
+            .get(self.start..self.end.min(source.len()))
+            .map_or(1, |token| token.chars().count().max(1));
+        format!(
+            "{}\n  {}\n  {}{}",
+            self.message,
+            source,
+            " ".repeat(start),
+            "^".repeat(width)
+        )
+    }
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at offset {})", self.message, self.start)
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+impl From<FilterError> for PolarsError {
+    fn from(err: FilterError) -> Self {
+        PolarsError::ComputeError(err.to_string().into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    NotEq,
+    Gt,
+    GtEq,
+    Lt,
+    LtEq,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Ident(String),
+    Number(f64),
+    Text(String),
+    Op(Comparison),
+    And,
+    Or,
+    Not,
+    In,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    End,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Ident(name) => write!(f, "`{}`", name),
+            TokenKind::Number(number) => write!(f, "`{}`", number),
+            TokenKind::Text(text) => write!(f, "{:?}", text),
+            TokenKind::Op(op) => write!(f, "`{}`", op.symbol()),
+            TokenKind::And => f.write_str("`and`"),
+            TokenKind::Or => f.write_str("`or`"),
+            TokenKind::Not => f.write_str("`not`"),
+            TokenKind::In => f.write_str("`in`"),
+            TokenKind::LParen => f.write_str("`(`"),
+            TokenKind::RParen => f.write_str("`)`"),
+            TokenKind::LBracket => f.write_str("`[`"),
+            TokenKind::RBracket => f.write_str("`]`"),
+            TokenKind::Comma => f.write_str("`,`"),
+            TokenKind::End => f.write_str("the end of the filter"),
+        }
+    }
+}
+
+impl Comparison {
+    fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Eq => "==",
+            Comparison::NotEq => "!=",
+            Comparison::Gt => ">",
+            Comparison::GtEq => ">=",
+            Comparison::Lt => "<",
+            Comparison::LtEq => "<=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, FilterError> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        let two = source.get(i..i + 2).unwrap_or("");
+        let kind = match c {
+            b' ' | b'\t' | b'\n' | b'\r' => {
+                i += 1;
+                continue;
+            }
+            b'(' => TokenKind::LParen,
+            b')' => TokenKind::RParen,
+            b'[' => TokenKind::LBracket,
+            b']' => TokenKind::RBracket,
+            b',' => TokenKind::Comma,
+            _ if ["==", "!=", ">=", "<=", "&&", "||"].contains(&two) => {
+                i += 2;
+                tokens.push(Token {
+                    kind: match two {
+                        "==" => TokenKind::Op(Comparison::Eq),
+                        "!=" => TokenKind::Op(Comparison::NotEq),
+                        ">=" => TokenKind::Op(Comparison::GtEq),
+                        "<=" => TokenKind::Op(Comparison::LtEq),
+                        "&&" => TokenKind::And,
+                        _ => TokenKind::Or,
+                    },
+                    start,
+                    end: i,
+                });
+                continue;
+            }
+            b'>' => TokenKind::Op(Comparison::Gt),
+            b'<' => TokenKind::Op(Comparison::Lt),
+            b'!' => TokenKind::Not,
+            b'=' => {
+                return Err(FilterError::new(
+                    "use `==` to compare for equality",
+                    start,
+                    start + 1,
+                ))
+            }
+            b'"' => {
+                let mut text = String::new();
+                let mut chars = source[i + 1..].char_indices();
+                loop {
+                    match chars.next() {
+                        Some((offset, '"')) => {
+                            i += 1 + offset + 1;
+                            break;
+                        }
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, escaped @ ('"' | '\\'))) => text.push(escaped),
+                            Some((offset, other)) => {
+                                let at = i + 1 + offset;
+                                return Err(FilterError::new(
+                                    format!("unknown escape `\\{}`", other),
+                                    at - 1,
+                                    at + other.len_utf8(),
+                                ));
+                            }
+                            None => {
+                                return Err(FilterError::new(
+                                    "unterminated text, missing closing `\"`",
+                                    start,
+                                    source.len(),
+                                ))
+                            }
+                        },
+                        Some((_, other)) => text.push(other),
+                        None => {
+                            return Err(FilterError::new(
+                                "unterminated text, missing closing `\"`",
+                                start,
+                                source.len(),
+                            ))
+                        }
+                    }
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Text(text),
+                    start,
+                    end: i,
+                });
+                continue;
+            }
+            b'0'..=b'9' | b'-' | b'.' => {
+                i += 1;
+                while i < bytes.len() {
+                    match bytes[i] {
+                        b'0'..=b'9' | b'.' | b'_' => i += 1,
+                        // An exponent, which may be signed.
+                        b'e' | b'E' => {
+                            i += 1;
+                            if i < bytes.len() && matches!(bytes[i], b'+' | b'-') {
+                                i += 1;
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+                let literal = source[start..i].replace('_', "");
+                let number = literal.parse::<f64>().map_err(|_| {
+                    FilterError::new(format!("invalid number `{}`", &source[start..i]), start, i)
+                })?;
+                tokens.push(Token {
+                    kind: TokenKind::Number(number),
+                    start,
+                    end: i,
+                });
+                continue;
+            }
+            _ if c.is_ascii_alphabetic() || c == b'_' => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                let word = &source[start..i];
+                tokens.push(Token {
+                    kind: match word {
+                        "and" => TokenKind::And,
+                        "or" => TokenKind::Or,
+                        "not" => TokenKind::Not,
+                        "in" => TokenKind::In,
+                        _ => TokenKind::Ident(word.to_string()),
+                    },
+                    start,
+                    end: i,
+                });
+                continue;
+            }
+            _ => {
+                let other = source[i..].chars().next().unwrap();
+                return Err(FilterError::new(
+                    format!("unexpected character `{}`", other),
+                    start,
+                    start + other.len_utf8(),
+                ));
+            }
+        };
+        i += 1;
+        tokens.push(Token {
+            kind,
+            start,
+            end: i,
+        });
+    }
+    tokens.push(Token {
+        kind: TokenKind::End,
+        start: source.len(),
+        end: source.len(),
+    });
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Text(String),
+}
+
+impl Value {
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "a number",
+            Value::Text(_) => "text",
+        }
+    }
+
+    fn lit(&self) -> Expr {
+        match self {
+            Value::Number(number) => lit(*number),
+            Value::Text(text) => lit(text.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Spanned<T> {
+    node: T,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Or(Box<Node>, Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Compare {
+        column: Spanned<String>,
+        op: Comparison,
+        value: Spanned<Value>,
+    },
+    In {
+        column: Spanned<String>,
+        values: Vec<Spanned<Value>>,
+        negated: bool,
+    },
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    // Parentheses and `not`s around the current token.
+    depth: usize,
+    comparisons: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position]
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.position].clone();
+        if token.kind != TokenKind::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn unexpected(token: &Token, expected: &str) -> FilterError {
+        FilterError::new(
+            format!("expected {}, found {}", expected, token.kind),
+            token.start,
+            token.end,
+        )
+    }
+
+    fn expect(&mut self, kind: TokenKind, expected: &str) -> Result<Token, FilterError> {
+        let token = self.next();
+        if token.kind == kind {
+            Ok(token)
+        } else {
+            Err(Self::unexpected(&token, expected))
+        }
+    }
+
+    fn or(&mut self) -> Result<Node, FilterError> {
+        let mut node = self.and()?;
+        while self.peek().kind == TokenKind::Or {
+            self.next();
+            node = Node::Or(Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node, FilterError> {
+        let mut node = self.unary()?;
+        while self.peek().kind == TokenKind::And {
+            self.next();
+            node = Node::And(Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    // Consumes a `(` or `not`, one level deeper.
+    fn nest(&mut self) -> Result<(), FilterError> {
+        let token = self.next();
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(FilterError::new(
+                format!("filter nests deeper than {} levels", MAX_DEPTH),
+                token.start,
+                token.end,
+            ));
+        }
+        Ok(())
+    }
+
+    // Counts a comparison, or a value of an `in` list (each compiles to
+    // one).
+    fn count(&mut self, start: usize, end: usize) -> Result<(), FilterError> {
+        self.comparisons += 1;
+        if self.comparisons > MAX_COMPARISONS {
+            return Err(FilterError::new(
+                format!("filter has more than {} comparisons", MAX_COMPARISONS),
+                start,
+                end,
+            ));
+        }
+        Ok(())
+    }
+
+    fn unary(&mut self) -> Result<Node, FilterError> {
+        let node = match self.peek().kind {
+            TokenKind::Not => {
+                self.nest()?;
+                Node::Not(Box::new(self.unary()?))
+            }
+            TokenKind::LParen => {
+                self.nest()?;
+                let node = self.or()?;
+                self.expect(TokenKind::RParen, "`)` or an operator")?;
+                node
+            }
+            _ => return self.comparison(),
+        };
+        self.depth -= 1;
+        Ok(node)
+    }
+
+    fn value(&mut self) -> Result<Spanned<Value>, FilterError> {
+        let token = self.next();
+        let node = match token.kind {
+            TokenKind::Number(number) => Value::Number(number),
+            TokenKind::Text(text) => Value::Text(text),
+            TokenKind::Ident(ref name) => {
+                return Err(FilterError::new(
+                    format!("expected a value, found column `{name}`; quote text: \"{name}\""),
+                    token.start,
+                    token.end,
+                ))
+            }
+            _ => return Err(Self::unexpected(&token, "a number or \"text\"")),
+        };
+        Ok(Spanned {
+            node,
+            start: token.start,
+            end: token.end,
+        })
+    }
+
+    fn comparison(&mut self) -> Result<Node, FilterError> {
+        let token = self.next();
+        let TokenKind::Ident(name) = token.kind.clone() else {
+            return Err(Self::unexpected(&token, "a column name, `not` or `(`"));
+        };
+        self.count(token.start, token.end)?;
+        let column = Spanned {
+            node: name,
+            start: token.start,
+            end: token.end,
+        };
+        let negated = self.peek().kind == TokenKind::Not;
+        if negated {
+            self.next();
+            if self.peek().kind != TokenKind::In {
+                return Err(Self::unexpected(self.peek(), "`in` after `not`"));
+            }
+        }
+        let token = self.next();
+        match token.kind {
+            TokenKind::Op(op) => Ok(Node::Compare {
+                column,
+                op,
+                value: self.value()?,
+            }),
+            TokenKind::In => {
+                self.expect(TokenKind::LBracket, "`[` to start the list")?;
+                let mut values = vec![self.value()?];
+                while self.peek().kind == TokenKind::Comma {
+                    self.next();
+                    let value = self.value()?;
+                    self.count(value.start, value.end)?;
+                    values.push(value);
+                }
+                self.expect(TokenKind::RBracket, "`,` or `]`")?;
+                Ok(Node::In {
+                    column,
+                    values,
+                    negated,
+                })
+            }
+            _ => Err(Self::unexpected(
+                &token,
+                &format!("a comparison or `in` after `{}`", column.node),
+            )),
+        }
+    }
+}
+
+// A parsed filter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    root: Node,
+}
+
+pub fn parse(source: &str) -> Result<Filter, FilterError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        position: 0,
+        depth: 0,
+        comparisons: 0,
+    };
+    let root = parser.or()?;
+    let rest = parser.peek();
+    if rest.kind != TokenKind::End {
+        return Err(Parser::unexpected(
+            rest,
+            "`and`, `or` or the end of the filter",
+        ));
+    }
+    Ok(Filter { root })
+}
+
+fn check_column(schema: &Schema, column: &Spanned<String>) -> Result<&'static str, FilterError> {
+    let Some(dtype) = schema.get(&column.node) else {
+        let closest = schema
+            .iter_names()
+            .map(|name| (similarity(name, &column.node), name))
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .filter(|(score, _)| *score >= 0.5);
+        let hint = match closest {
+            Some((_, name)) => format!(", did you mean `{}`?", name),
+            None => String::new(),
+        };
+        return Err(FilterError::new(
+            format!("unknown column `{}`{}", column.node, hint),
+            column.start,
+            column.end,
+        ));
+    };
+    match dtype {
+        DataType::Utf8 => Ok("text"),
+        dtype if dtype.is_numeric() => Ok("a number"),
+        other => Err(FilterError::new(
+            format!(
+                "column `{}` has type {}, which filters cannot compare",
+                column.node, other
+            ),
+            column.start,
+            column.end,
+        )),
+    }
+}
+
+fn check_value(
+    column: &Spanned<String>,
+    expected: &str,
+    value: &Spanned<Value>,
+) -> Result<(), FilterError> {
+    if value.node.kind() == expected {
+        return Ok(());
+    }
+    Err(FilterError::new(
+        format!(
+            "`{}` holds {}, but this is {}",
+            column.node,
+            expected,
+            value.node.kind()
+        ),
+        value.start,
+        value.end,
+    ))
+}
+
+fn check(node: &Node, schema: &Schema) -> Result<(), FilterError> {
+    match node {
+        Node::Or(left, right) | Node::And(left, right) => {
+            check(left, schema)?;
+            check(right, schema)
+        }
+        Node::Not(inner) => check(inner, schema),
+        Node::Compare { column, value, .. } => {
+            check_value(column, check_column(schema, column)?, value)
+        }
+        Node::In { column, values, .. } => {
+            let expected = check_column(schema, column)?;
+            values
+                .iter()
+                .try_for_each(|value| check_value(column, expected, value))
+        }
+    }
+}
+
+fn compile(node: &Node) -> Expr {
+    match node {
+        Node::Or(left, right) => compile(left).or(compile(right)),
+        Node::And(left, right) => compile(left).and(compile(right)),
+        Node::Not(inner) => compile(inner).not(),
+        Node::Compare { column, op, value } => {
+            let (column, value) = (col(&column.node), value.node.lit());
+            match op {
+                Comparison::Eq => column.eq(value),
+                Comparison::NotEq => column.neq(value),
+                Comparison::Gt => column.gt(value),
+                Comparison::GtEq => column.gt_eq(value),
+                Comparison::Lt => column.lt(value),
+                Comparison::LtEq => column.lt_eq(value),
+            }
+        }
+        Node::In {
+            column,
+            values,
+            negated,
+        } => {
+            let any = values
+                .iter()
+                .map(|value| col(&column.node).eq(value.node.lit()))
+                .reduce(Expr::or)
+                .unwrap();
+            if *negated {
+                any.not()
+            } else {
+                any
+            }
+        }
+    }
+}
+
+impl Filter {
+    // Checks every column exists and is compared with a value of its type.
+    pub fn check(&self, schema: &Schema) -> Result<(), FilterError> {
+        check(&self.root, schema)
+    }
+
+    // The filter as a boolean expression; `check` it first.
+    pub fn to_expr(&self) -> Expr {
+        compile(&self.root)
+    }
+
+    // The filter fully parenthesized, with numbers as their exact bits and
+    // text escaped, for cache keys: two filters have the same key exactly
+    // when they parse to the same tree.
+    pub fn key(&self) -> String {
+        let mut key = String::new();
+        write_key(&self.root, &mut key);
+        key
+    }
+}
+
+fn write_value(value: &Value, key: &mut String) {
+    match value {
+        Value::Number(number) => key.push_str(&format!("{:016x}", number.to_bits())),
+        Value::Text(text) => key.push_str(&format!("{:?}", text)),
+    }
+}
+
+fn write_key(node: &Node, key: &mut String) {
+    match node {
+        Node::Or(left, right) | Node::And(left, right) => {
+            key.push('(');
+            write_key(left, key);
+            key.push_str(if matches!(node, Node::Or(..)) {
+                " or "
+            } else {
+                " and "
+            });
+            write_key(right, key);
+            key.push(')');
+        }
+        Node::Not(inner) => {
+            key.push_str("(not ");
+            write_key(inner, key);
+            key.push(')');
+        }
+        Node::Compare { column, op, value } => {
+            key.push_str(&format!("({:?} {} ", column.node, op.symbol()));
+            write_value(&value.node, key);
+            key.push(')');
+        }
+        Node::In {
+            column,
+            values,
+            negated,
+        } => {
+            key.push_str(&format!("({:?} ", column.node));
+            if *negated {
+                key.push_str("not ");
+            }
+            key.push_str("in [");
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    key.push_str(", ");
+                }
+                write_value(&value.node, key);
+            }
+            key.push_str("])");
+        }
+    }
+}
+
+// Parses `source` and checks it against `schema`.
+pub fn check_filter(source: &str, schema: &Schema) -> Result<Filter, FilterError> {
+    let filter = parse(source)?;
+    filter.check(schema)?;
+    Ok(filter)
+}
+
+// Parses, checks and compiles `source` against `schema`.
+pub fn compile_filter(source: &str, schema: &Schema) -> Result<Expr, FilterError> {
+    Ok(check_filter(source, schema)?.to_expr())
+}
+
+// The rows of `df` matching the filter `source`.
+pub fn filter_frame(df: &DataFrame, source: &str) -> Result<DataFrame, PolarsError> {
+    let expr = compile_filter(source, &df.schema())?;
+    df.clone().lazy().filter(expr).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Schema {
+        Schema::from_iter([
+            Field::new("team", DataType::Utf8),
+            Field::new("position", DataType::Utf8),
+            Field::new("salary", DataType::Float64),
+        ])
+    }
+
+    fn kinds(source: &str) -> Vec<TokenKind> {
+        tokenize(source)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect()
+    }
+
+    // The tree in prefix form, e.g. `(or salary>1 (not team=="A"))`.
+    fn shape(node: &Node) -> String {
+        let value = |value: &Value| match value {
+            Value::Number(number) => number.to_string(),
+            Value::Text(text) => format!("{:?}", text),
+        };
+        match node {
+            Node::Or(left, right) => format!("(or {} {})", shape(left), shape(right)),
+            Node::And(left, right) => format!("(and {} {})", shape(left), shape(right)),
+            Node::Not(inner) => format!("(not {})", shape(inner)),
+            Node::Compare {
+                column,
+                op,
+                value: v,
+            } => {
+                format!("{}{}{}", column.node, op.symbol(), value(&v.node))
+            }
+            Node::In {
+                column,
+                values,
+                negated,
+            } => format!(
+                "{}{}in[{}]",
+                column.node,
+                if *negated { " not " } else { " " },
+                values
+                    .iter()
+                    .map(|v| value(&v.node))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        }
+    }
+
+    fn parsed(source: &str) -> String {
+        shape(&parse(source).unwrap().root)
+    }
+
+    fn error(source: &str) -> FilterError {
+        parse(source)
+            .and_then(|filter| filter.check(&schema()))
+            .unwrap_err()
+    }
+
+    #[test]
+    fn tokenizes_operators_numbers_and_text() {
+        assert_eq!(
+            kinds(r#"salary>=1_000&&team!="LA \"FC\"\\"||!(x<2)"#),
+            vec![
+                TokenKind::Ident("salary".into()),
+                TokenKind::Op(Comparison::GtEq),
+                TokenKind::Number(1000.0),
+                TokenKind::And,
+                TokenKind::Ident("team".into()),
+                TokenKind::Op(Comparison::NotEq),
+                TokenKind::Text(r#"LA "FC"\"#.into()),
+                TokenKind::Or,
+                TokenKind::Not,
+                TokenKind::LParen,
+                TokenKind::Ident("x".into()),
+                TokenKind::Op(Comparison::Lt),
+                TokenKind::Number(2.0),
+                TokenKind::RParen,
+                TokenKind::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenizes_signed_exponents() {
+        assert_eq!(
+            kinds("1e-5 2.5E+3 -4e2 .5"),
+            vec![
+                TokenKind::Number(1e-5),
+                TokenKind::Number(2500.0),
+                TokenKind::Number(-400.0),
+                TokenKind::Number(0.5),
+                TokenKind::End,
+            ]
+        );
+        assert_eq!(parsed("salary > 1e-5"), "salary>0.00001");
+        assert_eq!(error("salary > 1e").message, "invalid number `1e`");
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            parsed("salary > 1 or salary < 2 and team == \"A\""),
+            r#"(or salary>1 (and salary<2 team=="A"))"#
+        );
+        assert_eq!(
+            parsed("(salary > 1 or salary < 2) and team == \"A\""),
+            r#"(and (or salary>1 salary<2) team=="A")"#
+        );
+        assert_eq!(
+            parsed("not salary > 1 and not not team == \"A\""),
+            r#"(and (not salary>1) (not (not team=="A")))"#
+        );
+        assert_eq!(
+            parsed("salary > 1 or salary > 2 or salary > 3"),
+            "(or (or salary>1 salary>2) salary>3)"
+        );
+    }
+
+    #[test]
+    fn parses_in_lists() {
+        assert_eq!(
+            parsed(r#"position in ["D", "D-M"]"#),
+            r#"position in["D","D-M"]"#
+        );
+        assert_eq!(parsed("salary not in [1, 2]"), "salary not in[1,2]");
+        assert_eq!(
+            error("position in []").message,
+            "expected a number or \"text\", found `]`"
+        );
+        assert_eq!(
+            error("position not == \"D\"").message,
+            "expected `in` after `not`, found `==`"
+        );
+
+        let df = df!(
+            "team" => ["A", "B", "C"],
+            "position" => ["D", "M", "D-M"],
+            "salary" => [1.0, 2.0, 3.0],
+        )
+        .unwrap();
+        let teams = |source| {
+            let rows = filter_frame(&df, source).unwrap();
+            let teams = rows.column("team").unwrap().utf8().unwrap();
+            teams.into_no_null_iter().collect::<Vec<_>>().join("")
+        };
+        assert_eq!(teams(r#"position in ["D", "D-M"]"#), "AC");
+        assert_eq!(teams(r#"position not in ["D", "D-M"]"#), "B");
+    }
+
+    #[test]
+    fn checks_columns_and_value_types() {
+        let unknown = error("salry > 500000");
+        assert_eq!(
+            unknown.message,
+            "unknown column `salry`, did you mean `salary`?"
+        );
+        assert_eq!((unknown.start, unknown.end), (0, 5));
+
+        let text = error(r#"salary > "a lot""#);
+        assert_eq!(text.message, "`salary` holds a number, but this is text");
+        assert_eq!((text.start, text.end), (9, 16));
+
+        let number = error(r#"team in ["A", 3]"#);
+        assert_eq!(number.message, "`team` holds text, but this is a number");
+        assert_eq!((number.start, number.end), (14, 15));
+    }
+
+    #[test]
+    fn renders_a_caret_under_the_token() {
+        let source = "salary > 1 and salry > 2";
+        assert_eq!(
+            error(source).render(source),
+            "unknown column `salry`, did you mean `salary`?\n  \
+             salary > 1 and salry > 2\n  \
+             \x20              ^^^^^"
+        );
+        // Past the end, at least one caret.
+        let source = "salary >";
+        assert_eq!(
+            error(source).render(source),
+            "expected a number or \"text\", found the end of the filter\n  \
+             salary >\n  \
+             \x20       ^"
+        );
+    }
+
+    #[test]
+    fn keys_tell_apart_every_distinct_filter() {
+        let key = |source| parse(source).unwrap().key();
+        assert_eq!(key("salary > 100000"), key("(salary>1e5)"));
+        assert_eq!(key("salary > 100_000"), key("salary > 100000.0"));
+        assert_eq!(key("not a == 1 && b == 2"), key("(not a == 1) and b == 2"));
+        for (a, b) in [
+            ("salary > 99999.9999999", "salary > 100000"),
+            ("salary > 500000.123456789", "salary > 500000.123457"),
+            ("salary > 1e21", "salary > 1.00001e21"),
+            (
+                r#"team == "x\"" or team == "y""#,
+                r#"team == "x\\" or team == "y""#,
+            ),
+            (r#"team == "a) or (b""#, r#"team == "a" or team == "b""#),
+            (
+                "a == 1 or b == 2 and c == 3",
+                "(a == 1 or b == 2) and c == 3",
+            ),
+            ("a in [1, 2]", "a not in [1, 2]"),
+        ] {
+            assert_ne!(key(a), key(b), "{} and {}", a, b);
+        }
+    }
+
+    #[test]
+    fn limits_nesting_depth() {
+        let nested = |depth| format!("{}salary > 1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+
+        // Deep enough to overflow the stack without the limit.
+        let err = parse(&nested(3000)).unwrap_err();
+        assert_eq!(
+            err.message,
+            format!("filter nests deeper than {} levels", MAX_DEPTH)
+        );
+        assert_eq!((err.start, err.end), (MAX_DEPTH, MAX_DEPTH + 1));
+
+        let nots = format!("{}salary > 1", "not ".repeat(3000));
+        let err = parse(&nots).unwrap_err();
+        assert_eq!((err.start, err.end), (4 * MAX_DEPTH, 4 * MAX_DEPTH + 3));
+
+        // Sibling parentheses don't add up.
+        let siblings = vec![nested(MAX_DEPTH); 3].join(" or ");
+        assert!(parse(&siblings).is_ok());
+    }
+
+    #[test]
+    fn limits_comparisons() {
+        let chain = |n| vec!["salary > 1"; n].join(" or ");
+        assert!(parse(&chain(MAX_COMPARISONS)).is_ok());
+        let err = parse(&chain(MAX_COMPARISONS + 1)).unwrap_err();
+        assert_eq!(
+            err.message,
+            format!("filter has more than {} comparisons", MAX_COMPARISONS)
+        );
+
+        let list = format!("salary in [{}]", vec!["1"; MAX_COMPARISONS + 1].join(", "));
+        assert!(parse(&list).is_err());
+    }
+}
@@ -0,0 +1,330 @@
+// GraphQL schema over players, teams and salary aggregates.
+//
+// Lets a client fetch a team, its players and threshold counts in one round
+// trip, e.g.
+//
+//   { team(name: "LAFC") { payroll playersAbove(threshold: 1000000)
+//                          players(threshold: 1000000) { lastName salary } } }
+//
+// `threshold` arguments mirror `calculate`: only salaries strictly above it
+// count. Every field reads the league's cached DataFrame (`League::frame`).
+// Queries deeper than MAX_DEPTH or costlier than MAX_COMPLEXITY are rejected
+// before they run; list fields cost LIST_COST times their selection.
+
+use crate::leagues::{league, League, DEFAULT_LEAGUE};
+use crate::{players, Player};
+use async_graphql::{
+    ComplexObject, EmptyMutation, EmptySubscription, Object, Result, Schema, SimpleObject,
+};
+use polars::prelude::DataFrame;
+
+// No type refers back to itself, so data queries nest at most three levels
+// (teams, players, fields) and the depth limit only bounds introspection,
+// whose `ofType` chains can nest without end. The introspection query
+// GraphiQL sends is thirteen levels deep (seven `ofType`s under a field
+// argument's type); fifteen leaves room for clients that unwrap a few more.
+pub const MAX_DEPTH: usize = 15;
+pub const MAX_COMPLEXITY: usize = 1000;
+const LIST_COST: usize = 10;
+
+pub type SalariesSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+pub fn schema() -> SalariesSchema {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+fn find(league_id: Option<String>) -> Result<&'static League> {
+    let id = league_id.as_deref().unwrap_or(DEFAULT_LEAGUE);
+    Ok(league(id).ok_or_else(|| format!("unknown league {}", id))?)
+}
+
+fn frame(league_id: Option<String>) -> Result<DataFrame> {
+    Ok(find(league_id)?.frame()?)
+}
+
+fn roster(league_id: Option<String>) -> Result<Vec<Player>> {
+    Ok(players(&frame(league_id)?)?)
+}
+
+fn above(players: &[Player], threshold: Option<f64>) -> Vec<Player> {
+    players
+        .iter()
+        .filter(|player| threshold.is_none_or(|threshold| player.salary > threshold))
+        .cloned()
+        .collect()
+}
+
+#[Object]
+impl Player {
+    async fn first_name(&self) -> &str {
+        &self.first_name
+    }
+
+    async fn last_name(&self) -> &str {
+        &self.last_name
+    }
+
+    async fn team(&self) -> &str {
+        &self.team
+    }
+
+    async fn position(&self) -> &str {
+        &self.position
+    }
+
+    async fn salary(&self) -> f64 {
+        self.salary
+    }
+}
+
+// Summary of a set of salaries. All but `players` are null for an empty set.
+#[derive(SimpleObject)]
+pub struct SalaryStats {
+    players: usize,
+    payroll: f64,
+    mean: Option<f64>,
+    median: Option<f64>,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl SalaryStats {
+    fn new(players: &[Player]) -> Self {
+        let mut salaries: Vec<f64> = players.iter().map(|player| player.salary).collect();
+        salaries.sort_by(f64::total_cmp);
+        let payroll: f64 = salaries.iter().sum();
+        let count = salaries.len();
+        let median = match count {
+            0 => None,
+            _ if count % 2 == 1 => Some(salaries[count / 2]),
+            _ => Some((salaries[count / 2 - 1] + salaries[count / 2]) / 2.0),
+        };
+        SalaryStats {
+            players: count,
+            payroll,
+            mean: (count > 0).then(|| payroll / count as f64),
+            median,
+            min: salaries.first().copied(),
+            max: salaries.last().copied(),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Team {
+    name: String,
+    #[graphql(skip)]
+    roster: Vec<Player>,
+}
+
+#[ComplexObject]
+impl Team {
+    // The roster, or only the players above `threshold`.
+    #[graphql(complexity = "LIST_COST * child_complexity")]
+    async fn players(&self, threshold: Option<f64>) -> Vec<Player> {
+        above(&self.roster, threshold)
+    }
+
+    async fn players_above(&self, threshold: f64) -> usize {
+        above(&self.roster, Some(threshold)).len()
+    }
+
+    async fn payroll(&self) -> f64 {
+        self.roster.iter().map(|player| player.salary).sum()
+    }
+
+    async fn stats(&self, threshold: Option<f64>) -> SalaryStats {
+        SalaryStats::new(&above(&self.roster, threshold))
+    }
+}
+
+// A row of `calculate`'s table.
+#[derive(SimpleObject)]
+pub struct TeamCount {
+    team: String,
+    players: u32,
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    // Players of a league, optionally narrowed like the REST filters.
+    #[graphql(complexity = "LIST_COST * child_complexity")]
+    async fn players(
+        &self,
+        league: Option<String>,
+        team: Option<String>,
+        position: Option<String>,
+        threshold: Option<f64>,
+    ) -> Result<Vec<Player>> {
+        Ok(above(&roster(league)?, threshold)
+            .into_iter()
+            .filter(|player| team.as_ref().is_none_or(|team| player.team == *team))
+            .filter(|player| position.as_ref().is_none_or(|p| player.position == *p))
+            .collect())
+    }
+
+    async fn team(&self, name: String, league: Option<String>) -> Result<Option<Team>> {
+        let roster: Vec<Player> = roster(league)?
+            .into_iter()
+            .filter(|player| player.team == name)
+            .collect();
+        Ok((!roster.is_empty()).then_some(Team { name, roster }))
+    }
+
+    #[graphql(complexity = "LIST_COST * child_complexity")]
+    async fn teams(&self, league: Option<String>) -> Result<Vec<Team>> {
+        let mut teams: Vec<Team> = Vec::new();
+        for player in roster(league)? {
+            match teams.iter_mut().find(|team| team.name == player.team) {
+                Some(team) => team.roster.push(player),
+                None => teams.push(Team {
+                    name: player.team.clone(),
+                    roster: vec![player],
+                }),
+            }
+        }
+        teams.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(teams)
+    }
+
+    // Players above `threshold` per team, exactly like `calculate`.
+    #[graphql(complexity = "LIST_COST * child_complexity")]
+    async fn count_above(&self, threshold: f64, league: Option<String>) -> Result<Vec<TeamCount>> {
+        let counts = find(league)?.count_above(threshold)?;
+        let teams = counts.column("team")?.utf8()?;
+        let players = counts.column("position")?.u32()?;
+        let mut rows: Vec<TeamCount> = teams
+            .into_iter()
+            .zip(players)
+            .filter_map(|(team, players)| {
+                Some(TeamCount {
+                    team: team?.to_string(),
+                    players: players?,
+                })
+            })
+            .collect();
+        rows.sort_by(|a, b| a.team.cmp(&b.team));
+        Ok(rows)
+    }
+
+    async fn salary_stats(
+        &self,
+        league: Option<String>,
+        threshold: Option<f64>,
+    ) -> Result<SalaryStats> {
+        Ok(SalaryStats::new(&above(&roster(league)?, threshold)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // graphql-js's `getIntrospectionQuery()`, which GraphiQL sends to load
+    // the schema.
+    const INTROSPECTION: &str = r#"
+        query IntrospectionQuery {
+          __schema {
+            queryType { name }
+            mutationType { name }
+            subscriptionType { name }
+            types { ...FullType }
+            directives { name description locations args { ...InputValue } }
+          }
+        }
+        fragment FullType on __Type {
+          kind name description
+          fields(includeDeprecated: true) {
+            name description args { ...InputValue } type { ...TypeRef }
+            isDeprecated deprecationReason
+          }
+          inputFields { ...InputValue }
+          interfaces { ...TypeRef }
+          enumValues(includeDeprecated: true) {
+            name description isDeprecated deprecationReason
+          }
+          possibleTypes { ...TypeRef }
+        }
+        fragment InputValue on __InputValue {
+          name description type { ...TypeRef } defaultValue
+        }
+        fragment TypeRef on __Type {
+          kind name
+          ofType { kind name
+            ofType { kind name
+              ofType { kind name
+                ofType { kind name
+                  ofType { kind name
+                    ofType { kind name
+                      ofType { kind name } } } } } } }
+        }
+    "#;
+
+    // Errors from running `query`, as messages.
+    async fn errors(query: &str) -> Vec<String> {
+        let response = schema().execute(query).await;
+        response.errors.into_iter().map(|e| e.message).collect()
+    }
+
+    // A type lookup nesting `ofType` until the query is `depth` levels deep.
+    fn nested_type_query(depth: usize) -> String {
+        let nesting = depth - 2;
+        format!(
+            "{{ __type(name: \"Player\") {{ {} name {} }} }}",
+            "ofType {".repeat(nesting),
+            "}".repeat(nesting)
+        )
+    }
+
+    #[tokio::test]
+    async fn graphiql_can_introspect() {
+        assert_eq!(errors(INTROSPECTION).await, Vec::<String>::new());
+        // It is thirteen levels deep.
+        for (limit, rejected) in [(13, false), (12, true)] {
+            let limited = Schema::build(Query, EmptyMutation, EmptySubscription)
+                .limit_depth(limit)
+                .finish();
+            let response = limited.execute(INTROSPECTION).await;
+            assert_eq!(response.is_err(), rejected, "{}", limit);
+        }
+    }
+
+    #[tokio::test]
+    async fn deep_queries_are_rejected() {
+        assert_eq!(
+            errors(&nested_type_query(MAX_DEPTH)).await,
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            errors(&nested_type_query(MAX_DEPTH + 1)).await,
+            vec!["Query is nested too deep."]
+        );
+    }
+
+    #[tokio::test]
+    async fn aliased_queries_add_up_to_too_complex() {
+        // Each alias costs LIST_COST * LIST_COST: a list of teams, each
+        // with a list of players.
+        let alias = |i: usize| format!("t{}: teams {{ players {{ lastName }} }}", i);
+        let per_alias = LIST_COST * LIST_COST;
+        let fits = MAX_COMPLEXITY / per_alias;
+        let query = |aliases: usize| {
+            format!(
+                "{{ {} }}",
+                (0..aliases).map(alias).collect::<Vec<_>>().join(" ")
+            )
+        };
+
+        assert_eq!(errors(&query(fits)).await, Vec::<String>::new());
+        assert_eq!(
+            errors(&query(fits + 1)).await,
+            vec!["Query is too complex."]
+        );
+    }
+}
@@ -0,0 +1,215 @@
+// Payroll inequality metrics per team.
+//
+// Counting players above a threshold says nothing about how top-heavy a
+// roster is, so for every team this computes:
+//  - the Gini coefficient (0 = everyone paid the same, 1 = one player takes it all)
+//  - the Theil T index (0 = perfect equality, ln(n) = one player takes it all)
+//  - the share of payroll held by the top 1, 3 and 5 earners
+//  - the Lorenz curve as (cumulative player share, cumulative payroll share) points
+
+use crate::salaries_by;
+use polars::prelude::*;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+pub struct LorenzPoint {
+    pub population_share: f64,
+    pub payroll_share: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct TeamInequality {
+    pub team: String,
+    pub players: usize,
+    pub payroll: f64,
+    pub gini: f64,
+    pub theil: f64,
+    pub top1_share: f64,
+    pub top3_share: f64,
+    pub top5_share: f64,
+    pub lorenz: Vec<LorenzPoint>,
+}
+
+impl TeamInequality {
+    // Computes every metric for one team's salaries (in any order).
+    pub fn from_salaries(team: String, mut salaries: Vec<f64>) -> Self {
+        salaries.sort_by(f64::total_cmp);
+        let payroll: f64 = salaries.iter().sum();
+
+        TeamInequality {
+            team,
+            players: salaries.len(),
+            payroll,
+            gini: gini(&salaries, payroll),
+            theil: theil(&salaries, payroll),
+            top1_share: top_share(&salaries, payroll, 1),
+            top3_share: top_share(&salaries, payroll, 3),
+            top5_share: top_share(&salaries, payroll, 5),
+            lorenz: lorenz(&salaries, payroll),
+        }
+    }
+}
+
+// Returns the inequality metrics for every team, ordered by team name.
+pub fn team_inequality(df: &DataFrame) -> Result<Vec<TeamInequality>, PolarsError> {
+    let teams = salaries_by(df, "team")?
+        .into_iter()
+        .map(|(team, salaries)| TeamInequality::from_salaries(team, salaries))
+        .collect();
+    Ok(teams)
+}
+
+// The functions below expect `sorted` in ascending order and `payroll` to be
+// its sum. An empty or zero payroll is treated as perfectly equal.
+
+fn gini(sorted: &[f64], payroll: f64) -> f64 {
+    let n = sorted.len() as f64;
+    if sorted.is_empty() || payroll <= 0.0 {
+        return 0.0;
+    }
+    let weighted: f64 = sorted
+        .iter()
+        .enumerate()
+        .map(|(i, salary)| (i + 1) as f64 * salary)
+        .sum();
+    2.0 * weighted / (n * payroll) - (n + 1.0) / n
+}
+
+fn theil(sorted: &[f64], payroll: f64) -> f64 {
+    let n = sorted.len() as f64;
+    if sorted.is_empty() || payroll <= 0.0 {
+        return 0.0;
+    }
+    let mean = payroll / n;
+    sorted
+        .iter()
+        .filter(|salary| **salary > 0.0)
+        .map(|salary| {
+            let ratio = salary / mean;
+            ratio * ratio.ln()
+        })
+        .sum::<f64>()
+        / n
+}
+
+fn top_share(sorted: &[f64], payroll: f64, k: usize) -> f64 {
+    if payroll <= 0.0 {
+        return 0.0;
+    }
+    sorted.iter().rev().take(k).sum::<f64>() / payroll
+}
+
+fn lorenz(sorted: &[f64], payroll: f64) -> Vec<LorenzPoint> {
+    let n = sorted.len() as f64;
+    let mut points = vec![LorenzPoint {
+        population_share: 0.0,
+        payroll_share: 0.0,
+    }];
+    let mut cumulative = 0.0;
+    for (i, salary) in sorted.iter().enumerate() {
+        cumulative += salary;
+        points.push(LorenzPoint {
+            population_share: (i + 1) as f64 / n,
+            payroll_share: if payroll > 0.0 {
+                cumulative / payroll
+            } else {
+                (i + 1) as f64 / n
+            },
+        });
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    fn team(salaries: &[f64]) -> TeamInequality {
+        TeamInequality::from_salaries("T".to_string(), salaries.to_vec())
+    }
+
+    fn lorenz_points(team: &TeamInequality) -> Vec<(f64, f64)> {
+        team.lorenz
+            .iter()
+            .map(|point| (point.population_share, point.payroll_share))
+            .collect()
+    }
+
+    #[test]
+    fn spread_salaries() {
+        // Given out of order on purpose.
+        let team = team(&[3.0, 1.0, 4.0, 2.0]);
+        assert_eq!((team.players, team.payroll), (4, 10.0));
+        assert!(close(team.gini, 0.25));
+        let theil = [1.0, 2.0, 3.0, 4.0]
+            .iter()
+            .map(|salary: &f64| salary / 2.5 * (salary / 2.5).ln())
+            .sum::<f64>()
+            / 4.0;
+        assert!(close(team.theil, theil));
+        assert!(close(team.top1_share, 0.4));
+        assert!(close(team.top3_share, 0.9));
+        assert!(close(team.top5_share, 1.0));
+        let points = lorenz_points(&team);
+        let expected = [(0.0, 0.0), (0.25, 0.1), (0.5, 0.3), (0.75, 0.6), (1.0, 1.0)];
+        assert_eq!(points.len(), expected.len());
+        for (point, expected) in points.iter().zip(expected) {
+            assert!(close(point.0, expected.0) && close(point.1, expected.1));
+        }
+    }
+
+    #[test]
+    fn one_player_takes_it_all() {
+        let team = team(&[0.0, 0.0, 0.0, 100.0]);
+        // The most unequal n players can be: (n - 1) / n and ln(n).
+        assert!(close(team.gini, 0.75));
+        assert!(close(team.theil, 4f64.ln()));
+        assert!(close(team.top1_share, 1.0));
+    }
+
+    #[test]
+    fn equal_salaries_and_single_players_are_perfectly_equal() {
+        for salaries in [&[3.0, 3.0, 3.0][..], &[500_000.0]] {
+            let team = team(salaries);
+            assert!(close(team.gini, 0.0), "{:?}", salaries);
+            assert!(close(team.theil, 0.0), "{:?}", salaries);
+            let n = salaries.len() as f64;
+            for (i, point) in team.lorenz.iter().enumerate() {
+                assert!(close(point.population_share, i as f64 / n));
+                assert!(close(point.payroll_share, i as f64 / n));
+            }
+        }
+        let single = team(&[500_000.0]);
+        assert_eq!(
+            (single.top1_share, single.top3_share, single.top5_share),
+            (1.0, 1.0, 1.0)
+        );
+        let equal = team(&[3.0, 3.0, 3.0]);
+        assert!(close(equal.top1_share, 1.0 / 3.0));
+    }
+
+    #[test]
+    fn unpaid_teams_count_as_equal() {
+        let team = team(&[0.0, 0.0]);
+        assert_eq!((team.gini, team.theil, team.top1_share), (0.0, 0.0, 0.0));
+        assert_eq!(lorenz_points(&team), [(0.0, 0.0), (0.5, 0.5), (1.0, 1.0)]);
+    }
+
+    #[test]
+    fn teams_come_in_name_order() {
+        let df = df!(
+            "team" => ["B", "A", "B"],
+            "salary" => [1.0, 2.0, 3.0],
+        )
+        .unwrap();
+        let teams = team_inequality(&df).unwrap();
+        let names: Vec<_> = teams.iter().map(|team| team.team.as_str()).collect();
+        assert_eq!(names, ["A", "B"]);
+        assert_eq!(teams[1].payroll, 4.0);
+    }
+}
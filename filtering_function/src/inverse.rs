@@ -0,0 +1,158 @@
+// Inverse threshold queries.
+//
+// `calculate` answers "how many players earn more than X". These functions go
+// the other way and find the X that produces a given count, using the same
+// strict `salary > X` comparison so the answers can be fed straight back
+// into `calculate`.
+
+use crate::salaries_by;
+use polars::prelude::*;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct TeamCutoff {
+    pub team: String,
+    // Salary of the team's nth-highest earner.
+    pub nth_salary: Option<f64>,
+    // Lowest threshold that leaves exactly n players above it: the salary
+    // of the (n+1)th-highest earner.
+    pub threshold: Option<f64>,
+}
+
+// For every team, the salary of its nth-highest earner and the lowest
+// threshold that leaves exactly `n` of its players above it. Either is `None` when the
+// team has too few players or, for the threshold, when a tie at the cut-off
+// makes exactly `n` unreachable.
+pub fn nth_highest_per_team(df: &DataFrame, n: usize) -> Result<Vec<TeamCutoff>, PolarsError> {
+    let cutoffs = salaries_by(df, "team")?
+        .into_iter()
+        .map(|(team, mut salaries)| {
+            salaries.sort_by(|a, b| b.total_cmp(a));
+            TeamCutoff {
+                team,
+                nth_salary: n.checked_sub(1).and_then(|i| salaries.get(i).copied()),
+                threshold: cutoff(&salaries, n),
+            }
+        })
+        .collect();
+    Ok(cutoffs)
+}
+
+// The lowest threshold that leaves exactly `k` players league-wide earning
+// more than it: the (k+1)th-highest salary, since the comparison is strict.
+// Any value from there up to the kth-highest salary leaves the same `k`.
+// `None` when the kth- and (k+1)th-highest salaries tie, so that every
+// threshold keeps fewer or more than `k`, or when `k` is not below the
+// number of players (any value under the lowest salary keeps everyone).
+pub fn threshold_for(df: &DataFrame, k: usize) -> Result<Option<f64>, PolarsError> {
+    let salaries = df.column("salary")?.sort(true);
+    let salaries: Vec<f64> = salaries.f64()?.into_iter().flatten().collect();
+    Ok(cutoff(&salaries, k))
+}
+
+// `descending` must be sorted from highest to lowest salary. The value at
+// index `k` has exactly `k` salaries above it unless it ties the one before.
+fn cutoff(descending: &[f64], k: usize) -> Option<f64> {
+    let threshold = *descending.get(k)?;
+    match k.checked_sub(1) {
+        Some(last_kept) if descending[last_kept] <= threshold => None,
+        _ => Some(threshold),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame() -> DataFrame {
+        df!(
+            "team" => ["A", "A", "A", "A", "B"],
+            "salary" => [300.0, 100.0, 200.0, 200.0, 50.0],
+        )
+        .unwrap()
+    }
+
+    // Players earning strictly more than `threshold`, as `calculate` counts.
+    fn above(df: &DataFrame, threshold: f64) -> usize {
+        let salaries = df.column("salary").unwrap().f64().unwrap();
+        salaries
+            .into_iter()
+            .flatten()
+            .filter(|&s| s > threshold)
+            .count()
+    }
+
+    #[test]
+    fn cutoff_is_the_next_salary_down() {
+        let descending = [400.0, 300.0, 200.0, 100.0];
+        assert_eq!(cutoff(&descending, 1), Some(300.0));
+        assert_eq!(cutoff(&descending, 3), Some(100.0));
+    }
+
+    #[test]
+    fn cutoff_of_zero_is_the_top_salary() {
+        assert_eq!(cutoff(&[400.0, 300.0], 0), Some(400.0));
+        assert_eq!(cutoff(&[400.0, 400.0], 0), Some(400.0));
+    }
+
+    #[test]
+    fn cutoff_is_none_on_a_tie() {
+        let descending = [400.0, 300.0, 300.0, 100.0];
+        assert_eq!(cutoff(&descending, 2), None);
+        // The ties either side of it do not matter.
+        assert_eq!(cutoff(&descending, 1), Some(300.0));
+        assert_eq!(cutoff(&descending, 3), Some(100.0));
+    }
+
+    #[test]
+    fn cutoff_is_none_without_enough_players() {
+        let descending = [400.0, 300.0];
+        assert_eq!(cutoff(&descending, 2), None);
+        assert_eq!(cutoff(&descending, 5), None);
+        assert_eq!(cutoff(&[], 0), None);
+    }
+
+    #[test]
+    fn thresholds_leave_exactly_k_players() {
+        let df = frame();
+        for k in 0..df.height() {
+            match threshold_for(&df, k).unwrap() {
+                Some(threshold) => assert_eq!(above(&df, threshold), k, "k = {}", k),
+                // Only the two players on 200 tie.
+                None => assert_eq!(k, 2),
+            }
+        }
+        assert_eq!(threshold_for(&df, 5).unwrap(), None);
+    }
+
+    #[test]
+    fn nth_salaries_and_thresholds_per_team() {
+        let cutoffs = nth_highest_per_team(&frame(), 2).unwrap();
+        assert_eq!(
+            cutoffs,
+            vec![
+                TeamCutoff {
+                    team: "A".to_string(),
+                    nth_salary: Some(200.0),
+                    threshold: None,
+                },
+                TeamCutoff {
+                    team: "B".to_string(),
+                    nth_salary: None,
+                    threshold: None,
+                },
+            ]
+        );
+        let first = nth_highest_per_team(&frame(), 1).unwrap();
+        assert_eq!(first[0].nth_salary, Some(300.0));
+        assert_eq!(first[0].threshold, Some(200.0));
+        assert_eq!(first[1].nth_salary, Some(50.0));
+        assert_eq!(first[1].threshold, None);
+        // The 0th-highest earner does not exist, but a threshold at the top
+        // salary leaves no one above it.
+        let zeroth = nth_highest_per_team(&frame(), 0).unwrap();
+        assert_eq!(zeroth[0].nth_salary, None);
+        assert_eq!(zeroth[0].threshold, Some(300.0));
+    }
+}
@@ -0,0 +1,363 @@
+// League registry.
+//
+// Every league has the same salaries table shape. MLS ships embedded in the
+// binary as an Arrow file compiled by build.rs; the other leagues are read
+// from the CSV file named by an environment variable so their data can be
+// deployed alongside the Lambda. Those files are checked and cast to the
+// table shape as they load (see `conform_salaries`); a league whose file
+// doesn't fit fails to load, which routes answer with 503.
+// Frames returned by `League::load` carry an extra `league` column with the
+// league id, and `League::version` identifies the data they were read from.
+
+use crate::results::results;
+use crate::salary_index::SalaryIndex;
+use crate::{count_above, read_arrow, read_salaries, SALARIES_ARROW};
+use polars::prelude::*;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use utoipa::ToSchema;
+
+// The league served by the un-prefixed `/salaries/...` routes.
+pub const DEFAULT_LEAGUE: &str = "mls";
+
+enum Source {
+    // An Arrow IPC file.
+    Embedded(&'static [u8]),
+    // Environment variable holding the path of the league's CSV file.
+    File(&'static str),
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct League {
+    pub id: &'static str,
+    pub name: &'static str,
+    // ISO 4217 code of the salary figures.
+    pub currency: &'static str,
+    pub teams: &'static [&'static str],
+    #[serde(skip)]
+    source: Source,
+}
+
+pub const LEAGUES: &[League] = &[
+    League {
+        id: "mls",
+        name: "Major League Soccer",
+        currency: "USD",
+        teams: &[
+            "Atlanta United",
+            "Austin FC",
+            "CF Montreal",
+            "Charlotte FC",
+            "Chicago Fire",
+            "Colorado Rapids",
+            "Columbus Crew",
+            "DC United",
+            "FC Cincinnati",
+            "FC Dallas",
+            "Houston Dynamo",
+            "Inter Miami",
+            "LA Galaxy",
+            "LAFC",
+            "Minnesota United",
+            "Nashville SC",
+            "New England Revolution",
+            "New York City FC",
+            "New York Red Bulls",
+            "Orlando City SC",
+            "Philadelphia Union",
+            "Portland Timbers",
+            "Real Salt Lake",
+            "San Jose Earthquakes",
+            "Seattle Sounders FC",
+            "Sporting Kansas City",
+            "St. Louis City SC",
+            "Toronto FC",
+            "Vancouver Whitecaps",
+        ],
+        source: Source::Embedded(SALARIES_ARROW),
+    },
+    League {
+        id: "nwsl",
+        name: "National Women's Soccer League",
+        currency: "USD",
+        teams: &[
+            "Angel City FC",
+            "Chicago Red Stars",
+            "Houston Dash",
+            "Kansas City Current",
+            "NJ/NY Gotham FC",
+            "North Carolina Courage",
+            "OL Reign",
+            "Orlando Pride",
+            "Portland Thorns FC",
+            "Racing Louisville FC",
+            "San Diego Wave FC",
+            "Washington Spirit",
+        ],
+        source: Source::File("NWSL_SALARIES_CSV"),
+    },
+    League {
+        id: "usl",
+        name: "USL Championship",
+        currency: "USD",
+        teams: &[
+            "Birmingham Legion FC",
+            "Charleston Battery",
+            "Colorado Springs Switchbacks FC",
+            "Detroit City FC",
+            "El Paso Locomotive FC",
+            "FC Tulsa",
+            "Hartford Athletic",
+            "Indy Eleven",
+            "Las Vegas Lights FC",
+            "Louisville City FC",
+            "Loudoun United FC",
+            "Memphis 901 FC",
+            "Miami FC",
+            "Monterey Bay FC",
+            "New Mexico United",
+            "Oakland Roots SC",
+            "Orange County SC",
+            "Phoenix Rising FC",
+            "Pittsburgh Riverhounds SC",
+            "Rio Grande Valley FC",
+            "Sacramento Republic FC",
+            "San Antonio FC",
+            "San Diego Loyal SC",
+            "Tampa Bay Rowdies",
+        ],
+        source: Source::File("USL_SALARIES_CSV"),
+    },
+];
+
+// A league's salaries and the version of the data they were read from.
+#[derive(Clone)]
+struct Loaded {
+    df: DataFrame,
+    version: String,
+    // None when the frame has no usable team and salary columns.
+    index: Option<Arc<SalaryIndex>>,
+}
+
+// Frames loaded so far, by league id, so requests share one parsed copy.
+static LOADED: Mutex<BTreeMap<&str, Loaded>> = Mutex::new(BTreeMap::new());
+
+// Identifies the contents of a salaries file: the same bytes always give
+// the same version within a build, and replacing the file changes it.
+fn version(data: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+// Looks a league up by id, case-insensitively.
+pub fn league(id: &str) -> Option<&'static League> {
+    LEAGUES
+        .iter()
+        .find(|league| league.id.eq_ignore_ascii_case(id))
+}
+
+impl League {
+    fn read(&self) -> Result<Loaded, PolarsError> {
+        let (mut df, version) = match self.source {
+            Source::Embedded(arrow) => (read_arrow(arrow)?, version(arrow)),
+            Source::File(variable) => {
+                let path = std::env::var(variable).map_err(|_| {
+                    PolarsError::ComputeError(
+                        format!("no salary data for {}, set {}", self.id, variable).into(),
+                    )
+                })?;
+                let csv = std::fs::read_to_string(path)?;
+                (read_salaries(&csv)?, version(csv.as_bytes()))
+            }
+        };
+        df.with_column(Series::new("league", vec![self.id; df.height()]))?;
+        let index = match SalaryIndex::build(&df) {
+            Ok(index) => Some(Arc::new(index)),
+            Err(err) => {
+                tracing::warn!(league = self.id, %err, "no salary index, using full scans");
+                None
+            }
+        };
+        Ok(Loaded { df, version, index })
+    }
+
+    // Loads the league's salaries with a `league` column added.
+    pub fn load(&self) -> Result<DataFrame, PolarsError> {
+        Ok(self.read()?.df)
+    }
+
+    // `read`, done once per process. Failures are not cached, so a missing
+    // file is looked for again on the next call.
+    fn loaded(&self) -> Result<Loaded, PolarsError> {
+        let mut loaded = LOADED.lock().unwrap();
+        if let Some(league) = loaded.get(self.id) {
+            return Ok(league.clone());
+        }
+        let league = self.read()?;
+        loaded.insert(self.id, league.clone());
+        Ok(league)
+    }
+
+    // The cached salaries, see `loaded`.
+    pub fn frame(&self) -> Result<DataFrame, PolarsError> {
+        Ok(self.loaded()?.df)
+    }
+
+    // Players above `threshold` per team, as `count_above` on `frame` counts
+    // them, from the salary index when there is one.
+    pub fn count_above(&self, threshold: f64) -> Result<DataFrame, PolarsError> {
+        let loaded = self.loaded()?;
+        match loaded.index {
+            Some(index) => index.count_above_frame(threshold),
+            None => count_above(&loaded.df, threshold),
+        }
+    }
+
+    // The version of the cached salaries, a hash of the CSV they came from.
+    pub fn version(&self) -> Result<String, PolarsError> {
+        Ok(self.loaded()?.version)
+    }
+
+    // Loads the league again, picking up a replaced CSV file, and drops
+    // cached results of the old data. The cached frame is kept when the new
+    // data fails to load.
+    pub fn reload(&self) -> Result<DataFrame, PolarsError> {
+        let league = self.read()?;
+        let previous = LOADED.lock().unwrap().insert(self.id, league.clone());
+        if previous.is_none_or(|previous| previous.version != league.version) {
+            results().lock().unwrap().invalidate(self.id);
+        }
+        Ok(league.df)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::app;
+    use crate::conform_salaries;
+    use crate::currency::RateTable;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    const HEADER: &str = "first_name,last_name,team,position,salary\n";
+
+    // Points `variable` at a file holding `csv`. Each test uses its own
+    // league so they can run in parallel.
+    fn data_file(variable: &str, csv: &str) {
+        let path = std::env::temp_dir().join(format!("{}-{}.csv", variable, std::process::id()));
+        std::fs::write(&path, csv).unwrap();
+        std::env::set_var(variable, path);
+    }
+
+    async fn status(uri: &str) -> StatusCode {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = app::<Body>(RateTable::default())
+            .oneshot(request)
+            .await
+            .unwrap();
+        response.status()
+    }
+
+    #[test]
+    fn salaries_are_cast_to_their_types() {
+        let df = read_salaries(&format!("{}Ann,Lee,7,1,500000\nBo,Kim,7,2,\n", HEADER)).unwrap();
+        let dtypes: Vec<DataType> = df.dtypes();
+        assert_eq!(
+            dtypes,
+            [
+                DataType::Utf8,
+                DataType::Utf8,
+                DataType::Utf8,
+                DataType::Utf8,
+                DataType::Float64
+            ]
+        );
+        assert_eq!(
+            df.column("salary").unwrap().f64().unwrap().get(0),
+            Some(500000.0)
+        );
+
+        // Extra columns are dropped, so every league has the same shape.
+        let extra = df!(
+            "salary" => [1i64],
+            "first_name" => ["Ann"],
+            "last_name" => ["Lee"],
+            "team" => ["A"],
+            "position" => ["D"],
+            "age" => [30],
+        )
+        .unwrap();
+        assert_eq!(
+            conform_salaries(&extra).unwrap().get_column_names(),
+            crate::SALARY_COLUMNS
+        );
+    }
+
+    #[test]
+    fn malformed_salaries_are_rejected() {
+        let missing = read_salaries("first_name,last_name,team,salary\nAnn,Lee,A,1\n");
+        assert_eq!(
+            missing.unwrap_err().to_string(),
+            "salary data has no `position` column"
+        );
+        let text = read_salaries(&format!("{}Ann,Lee,A,D,$500k\n", HEADER));
+        assert_eq!(
+            text.unwrap_err().to_string(),
+            "salary data has str salaries, not numbers"
+        );
+    }
+
+    #[test]
+    fn salaries_without_players_are_empty() {
+        let df = read_salaries(HEADER).unwrap();
+        assert_eq!(df.height(), 0);
+        assert_eq!(df.column("salary").unwrap().dtype(), &DataType::Float64);
+    }
+
+    #[tokio::test]
+    async fn leagues_with_integer_salaries_load() {
+        data_file(
+            "NWSL_SALARIES_CSV",
+            &format!(
+                "{}Ann,Lee,Angel City,D,75000\nBo,Kim,Angel City,F,90000\n",
+                HEADER
+            ),
+        );
+        assert_eq!(
+            status("/leagues/nwsl/salaries/inequality").await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn leagues_with_bad_files_are_unavailable() {
+        data_file("USL_SALARIES_CSV", &format!("{}Ann,Lee,A,D,lots\n", HEADER));
+        assert_eq!(
+            status("/leagues/usl/salaries/inequality").await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        // Failures aren't cached, so a fixed file loads on the next request,
+        // and an export of no players is still named after the league.
+        data_file("USL_SALARIES_CSV", HEADER);
+        let request = Request::builder()
+            .uri("/leagues/usl/salaries/export.xlsx")
+            .body(Body::empty())
+            .unwrap();
+        let response = app::<Body>(RateTable::default())
+            .oneshot(request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[axum::http::header::CONTENT_DISPOSITION],
+            "attachment; filename=\"usl-salaries.xlsx\""
+        );
+    }
+}
@@ -1,34 +1,44 @@
-use axum::{extract::Path, response::Json, routing::get, Router};
+use axum::middleware;
 use lambda_http::{run, Error};
-use polars_lambda_axum::calculate;
-use serde_json::{json, Value};
-
-async fn root() -> &'static str {
-    "Hello, Polars"
-}
-
-//simple url: /iris/filter/5
-async fn get_filter(Path(value): Path<f64>) -> Json<Value> {
-    let df = calculate(value).unwrap();
-    let json = json!({
-        "payload": format!("{}", df),
-    });
-    Json(json)
-}
+use polars_lambda_axum::app::{app, open, protect};
+use polars_lambda_axum::auth::KeyStore;
+use polars_lambda_axum::cors::{cors, CorsPolicy};
+use polars_lambda_axum::currency::RateTable;
+use polars_lambda_axum::metrics::{record, Emf};
+use polars_lambda_axum::telemetry::{self, trace};
+use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
-    // required to enable CloudWatch error logging by the runtime
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::INFO)
-        // disable printing the name of the module in every log line.
-        .with_target(false)
-        // disabling time is handy because CloudWatch will add the ingestion time.
-        .without_time()
-        .init();
+    // JSON logs with a span per request, see telemetry.rs.
+    telemetry::init();
+
+    // Exchange rates for the `currency` parameter, from CURRENCY_RATES_FILE
+    // when set.
+    let rates = match std::env::var("CURRENCY_RATES_FILE") {
+        Ok(path) => RateTable::from_file(path)?,
+        Err(_) => RateTable::default(),
+    };
+
+    // API keys from API_KEYS_FILE or API_KEYS; without them the API is open
+    // but for the admin routes.
+    let app = match KeyStore::from_env()? {
+        Some(keys) => protect(app(rates), keys),
+        None => {
+            tracing::warn!("no API keys configured, admin routes are disabled");
+            open(app(rates))
+        }
+    };
+
+    // Browser access from CORS_ALLOWED_ORIGINS, see cors.rs. Outside the key
+    // check so preflights and rejections reach the page.
+    let app = match CorsPolicy::from_env()? {
+        Some(policy) => app.layer(middleware::from_fn_with_state(Arc::new(policy), cors)),
+        None => app,
+    };
+
+    // Request metrics as CloudWatch EMF log lines, see metrics.rs.
+    let app = app.layer(middleware::from_fn_with_state(Emf::stdout(), record));
 
-    let app = Router::new()
-        .route("/", get(root))
-        .route("/salaries/filter/:value", get(get_filter));
-    run(app).await
+    run(app.layer(middleware::from_fn(trace))).await
 }
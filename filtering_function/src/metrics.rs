@@ -0,0 +1,351 @@
+// Request metrics in CloudWatch Embedded Metric Format.
+//
+// `record` writes one JSON document per request to stdout; CloudWatch Logs
+// turns it into metrics in the METRICS_NAMESPACE namespace ("SalariesApi"
+// by default) with a `Route` dimension, without any API calls:
+//
+//   {"_aws":{"Timestamp":1700000000000,"CloudWatchMetrics":[{
+//     "Namespace":"SalariesApi","Dimensions":[["Route"]],
+//     "Metrics":[{"Name":"Latency","Unit":"Milliseconds"},…]}]},
+//    "Route":"/salaries/filter/:value","Method":"GET","Status":200,
+//    "Latency":4.1,"RowsScanned":924,"ResultSize":1530,
+//    "CacheHits":1,"CacheMisses":0,"Errors":0,"ClientErrors":0}
+//
+// Handlers count rows and cache lookups on the request's `Recorder`. Paths
+// matching no route share the Route "unmatched", so scans of random paths
+// don't each add a dimension value.
+
+use axum::{
+    body::HttpBody,
+    extract::{MatchedPath, State},
+    http::Request,
+    middleware::Next,
+    response::Response,
+};
+use serde_json::{json, Map, Value};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_NAMESPACE: &str = "SalariesApi";
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Counts {
+    rows_loaded: u64,
+    cache_hits: u64,
+    cache_misses: u64,
+}
+
+impl Counts {
+    // Loaded rows are scanned unless every result came from the cache.
+    fn rows_scanned(&self) -> u64 {
+        if self.cache_hits > 0 && self.cache_misses == 0 {
+            0
+        } else {
+            self.rows_loaded
+        }
+    }
+}
+
+// Counters for one request, found in the request extensions while `record`
+// is in the stack.
+#[derive(Clone, Default)]
+pub struct Recorder(Arc<Mutex<Counts>>);
+
+impl Recorder {
+    // The handler took a frame of `rows` rows to compute from.
+    pub fn rows_loaded(&self, rows: usize) {
+        self.0.lock().unwrap().rows_loaded += rows as u64;
+    }
+
+    pub fn cache(&self, hit: bool) {
+        let mut counts = self.0.lock().unwrap();
+        if hit {
+            counts.cache_hits += 1;
+        } else {
+            counts.cache_misses += 1;
+        }
+    }
+}
+
+// What one request measured.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestMetrics {
+    pub route: String,
+    pub method: String,
+    pub status: u16,
+    pub latency_ms: f64,
+    pub rows_scanned: u64,
+    // Response body bytes, None for bodies of unknown length.
+    pub result_size: Option<u64>,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+}
+
+impl RequestMetrics {
+    // The EMF document for these metrics at `timestamp` (milliseconds since
+    // the epoch).
+    pub fn document(&self, namespace: &str, timestamp: u64) -> Value {
+        let mut metrics = vec![
+            ("Latency", "Milliseconds", json!(self.latency_ms)),
+            ("RowsScanned", "Count", json!(self.rows_scanned)),
+        ];
+        if let Some(size) = self.result_size {
+            metrics.push(("ResultSize", "Bytes", json!(size)));
+        }
+        metrics.extend([
+            ("CacheHits", "Count", json!(self.cache_hits)),
+            ("CacheMisses", "Count", json!(self.cache_misses)),
+            ("Errors", "Count", json!(u64::from(self.status >= 500))),
+            (
+                "ClientErrors",
+                "Count",
+                json!(u64::from((400..500).contains(&self.status))),
+            ),
+        ]);
+
+        let mut document = Map::new();
+        document.insert(
+            "_aws".to_string(),
+            json!({
+                "Timestamp": timestamp,
+                "CloudWatchMetrics": [{
+                    "Namespace": namespace,
+                    "Dimensions": [["Route"]],
+                    "Metrics": metrics
+                        .iter()
+                        .map(|(name, unit, _)| json!({ "Name": name, "Unit": unit }))
+                        .collect::<Vec<_>>(),
+                }],
+            }),
+        );
+        document.insert("Route".to_string(), json!(self.route));
+        document.insert("Method".to_string(), json!(self.method));
+        document.insert("Status".to_string(), json!(self.status));
+        for (name, _, value) in metrics {
+            document.insert(name.to_string(), value);
+        }
+        Value::Object(document)
+    }
+}
+
+// Where `record` sends documents: stdout in the Lambda.
+#[derive(Clone)]
+pub struct Emf {
+    namespace: String,
+    sink: Arc<dyn Fn(String) + Send + Sync>,
+}
+
+impl Emf {
+    pub fn new(
+        namespace: impl Into<String>,
+        sink: impl Fn(String) + Send + Sync + 'static,
+    ) -> Self {
+        Emf {
+            namespace: namespace.into(),
+            sink: Arc::new(sink),
+        }
+    }
+
+    // Prints documents in METRICS_NAMESPACE, or DEFAULT_NAMESPACE.
+    pub fn stdout() -> Self {
+        let namespace =
+            std::env::var("METRICS_NAMESPACE").unwrap_or_else(|_| DEFAULT_NAMESPACE.to_string());
+        Emf::new(namespace, |document| println!("{}", document))
+    }
+}
+
+// Measures the request and emits its EMF document.
+pub async fn record<B>(State(emf): State<Emf>, mut request: Request<B>, next: Next<B>) -> Response {
+    let start = Instant::now();
+    let recorder = Recorder::default();
+    request.extensions_mut().insert(recorder.clone());
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or(UNMATCHED_ROUTE, MatchedPath::as_str)
+        .to_string();
+    let method = request.method().to_string();
+
+    let response = next.run(request).await;
+    let counts = *recorder.0.lock().unwrap();
+    let metrics = RequestMetrics {
+        route,
+        method,
+        status: response.status().as_u16(),
+        latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+        rows_scanned: counts.rows_scanned(),
+        result_size: response.body().size_hint().exact(),
+        cache_hits: counts.cache_hits,
+        cache_misses: counts.cache_misses,
+    };
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    (emf.sink)(metrics.document(&emf.namespace, timestamp).to_string());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::app;
+    use crate::currency::RateTable;
+    use axum::{body::Body, middleware};
+    use tower::ServiceExt;
+
+    fn metrics(status: u16, result_size: Option<u64>) -> RequestMetrics {
+        RequestMetrics {
+            route: "/salaries/filter/:value".to_string(),
+            method: "GET".to_string(),
+            status,
+            latency_ms: 4.5,
+            rows_scanned: 924,
+            result_size,
+            cache_hits: 1,
+            cache_misses: 0,
+        }
+    }
+
+    #[test]
+    fn document_declares_and_carries_every_metric() {
+        let document = metrics(200, Some(1530)).document("SalariesApi", 1_700_000_000_000);
+        assert_eq!(
+            document,
+            json!({
+                "_aws": {
+                    "Timestamp": 1_700_000_000_000u64,
+                    "CloudWatchMetrics": [{
+                        "Namespace": "SalariesApi",
+                        "Dimensions": [["Route"]],
+                        "Metrics": [
+                            {"Name": "Latency", "Unit": "Milliseconds"},
+                            {"Name": "RowsScanned", "Unit": "Count"},
+                            {"Name": "ResultSize", "Unit": "Bytes"},
+                            {"Name": "CacheHits", "Unit": "Count"},
+                            {"Name": "CacheMisses", "Unit": "Count"},
+                            {"Name": "Errors", "Unit": "Count"},
+                            {"Name": "ClientErrors", "Unit": "Count"},
+                        ],
+                    }],
+                },
+                "Route": "/salaries/filter/:value",
+                "Method": "GET",
+                "Status": 200,
+                "Latency": 4.5,
+                "RowsScanned": 924,
+                "ResultSize": 1530,
+                "CacheHits": 1,
+                "CacheMisses": 0,
+                "Errors": 0,
+                "ClientErrors": 0,
+            })
+        );
+    }
+
+    #[test]
+    fn document_counts_errors_by_class() {
+        let server = metrics(503, Some(10)).document("SalariesApi", 0);
+        assert_eq!(
+            (server["Errors"].clone(), server["ClientErrors"].clone()),
+            (json!(1), json!(0))
+        );
+        let client = metrics(404, Some(10)).document("SalariesApi", 0);
+        assert_eq!(
+            (client["Errors"].clone(), client["ClientErrors"].clone()),
+            (json!(0), json!(1))
+        );
+    }
+
+    #[test]
+    fn document_leaves_out_unknown_result_size() {
+        let document = metrics(200, None).document("SalariesApi", 0);
+        assert!(document.get("ResultSize").is_none());
+        let declared = &document["_aws"]["CloudWatchMetrics"][0]["Metrics"];
+        assert!(!declared
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|metric| metric["Name"] == "ResultSize"));
+    }
+
+    // The documents emitted for `uris`, requested in order from one app.
+    async fn emitted(uris: &[&str]) -> Vec<Value> {
+        let documents = Arc::new(Mutex::new(Vec::new()));
+        let sink = documents.clone();
+        let emf = Emf::new("Test", move |document| sink.lock().unwrap().push(document));
+        let router =
+            app::<Body>(RateTable::default()).layer(middleware::from_fn_with_state(emf, record));
+        for uri in uris {
+            let request = Request::builder().uri(*uri).body(Body::empty()).unwrap();
+            router.clone().oneshot(request).await.unwrap();
+        }
+        let documents = documents.lock().unwrap();
+        documents
+            .iter()
+            .map(|document| serde_json::from_str(document).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn filter_misses_count_the_rows_they_scan() {
+        let documents = emitted(&["/salaries/filter/1234567", "/salaries/filter/1234567"]).await;
+        assert_eq!(documents[0]["CacheMisses"], 1);
+        assert!(documents[0]["RowsScanned"].as_u64().unwrap() > 0);
+        assert_eq!(documents[1]["CacheHits"], 1);
+        assert_eq!(documents[1]["RowsScanned"], 0);
+    }
+
+    #[tokio::test]
+    async fn requests_emit_one_document_each() {
+        // A query no other test makes, so the first request misses the
+        // process-wide result cache.
+        let documents = emitted(&[
+            "/salaries/top/position/7",
+            "/leagues/mls/salaries/top/position/7",
+            "/leagues/nope/salaries/top/team/3",
+            "/wp-login.php",
+        ])
+        .await;
+        assert_eq!(documents.len(), 4);
+
+        let first = &documents[0];
+        assert_eq!(first["_aws"]["CloudWatchMetrics"][0]["Namespace"], "Test");
+        assert_eq!(first["Route"], "/salaries/top/:grouping/:n");
+        assert_eq!(first["Method"], "GET");
+        assert_eq!(first["Status"], 200);
+        assert!(first["RowsScanned"].as_u64().unwrap() > 0);
+        assert!(first["ResultSize"].as_u64().unwrap() > 0);
+        assert_eq!(
+            first["CacheHits"].as_u64().unwrap() + first["CacheMisses"].as_u64().unwrap(),
+            1
+        );
+
+        // The first request's result is cached, so nothing is scanned.
+        let second = &documents[1];
+        assert_eq!(
+            second["Route"],
+            "/leagues/:league/salaries/top/:grouping/:n"
+        );
+        assert_eq!(
+            (second["CacheHits"].clone(), second["CacheMisses"].clone()),
+            (json!(1), json!(0))
+        );
+        assert_eq!(second["RowsScanned"], 0);
+
+        let unknown = &documents[2];
+        assert_eq!(unknown["Status"], 404);
+        assert_eq!(
+            (
+                unknown["ClientErrors"].clone(),
+                unknown["RowsScanned"].clone()
+            ),
+            (json!(1), json!(0))
+        );
+
+        let unmatched = &documents[3];
+        assert_eq!(unmatched["Route"], UNMATCHED_ROUTE);
+        assert_eq!(unmatched["Status"], 404);
+    }
+}
@@ -0,0 +1,127 @@
+// Player name normalisation and fuzzy comparison.
+//
+// The embedded salaries CSV was saved as UTF-8 and later read back as Mac
+// Roman, so "Nicolás" appears as "Nicol√°s". Names from other sources are
+// usually spelled correctly, with or without accents. `normalize_name`
+// repairs the mis-decoding, folds accents to ASCII, lowercases and collapses
+// punctuation and whitespace so both spellings compare equal.
+
+// Mac Roman characters for bytes 0x80..=0xFF.
+const MAC_ROMAN_HIGH: &str = "ÄÅÇÉÑÖÜáàâäãåçéèêëíìîïñóòôöõúùûü†°¢£§•¶ß®©™´¨≠ÆØ∞±≤≥¥µ∂∑∏π∫ªºΩæø¿¡¬√ƒ≈∆«»…\u{a0}ÀÃÕŒœ–—“”‘’÷◊ÿŸ⁄€‹›ﬁﬂ‡·‚„‰ÂÊÁËÈÍÎÏÌÓÔ\u{f8ff}ÒÚÛÙıˆ˜¯˘˙˚¸˝˛ˇ";
+
+// Re-encodes `text` as Mac Roman and decodes the bytes as UTF-8. Returns the
+// input unchanged when it is not mis-decoded UTF-8.
+pub fn repair_mac_roman(text: &str) -> String {
+    if text.is_ascii() {
+        return text.to_string();
+    }
+    let bytes: Option<Vec<u8>> = text
+        .chars()
+        .map(|c| {
+            if c.is_ascii() {
+                Some(c as u8)
+            } else {
+                MAC_ROMAN_HIGH
+                    .chars()
+                    .position(|high| high == c)
+                    .map(|i| 0x80 + i as u8)
+            }
+        })
+        .collect();
+    bytes
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .unwrap_or_else(|| text.to_string())
+}
+
+fn fold(c: char) -> &'static str {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'č' => "c",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' | 'ě' => "e",
+        'ì' | 'í' | 'î' | 'ï' | 'ı' => "i",
+        'ñ' | 'ń' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'œ' => "oe",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => "u",
+        'ý' | 'ÿ' => "y",
+        'ß' => "ss",
+        'ł' => "l",
+        'ř' => "r",
+        'š' | 'ś' => "s",
+        'ž' | 'ź' | 'ż' => "z",
+        'đ' | 'ð' => "d",
+        'þ' => "th",
+        _ => "",
+    }
+}
+
+// "Nicol√°s  Acevedo" and "nicolas acevedo" both become "nicolas acevedo".
+pub fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in repair_mac_roman(name).chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            normalized.push(c);
+        } else if !c.is_ascii() && !fold(c).is_empty() {
+            normalized.push_str(fold(c));
+        } else if !normalized.is_empty() && !normalized.ends_with(' ') {
+            // Whitespace, hyphens, apostrophes and dots separate words.
+            normalized.push(' ');
+        }
+    }
+    normalized.trim_end().to_string()
+}
+
+// Similarity of two normalised names from 0 (nothing in common) to 1
+// (identical), based on the Levenshtein edit distance.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    1.0 - previous[b.len()] as f64 / longest as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mac_roman_mis_decoding_is_repaired() {
+        assert_eq!(repair_mac_roman("Nicol√°s"), "Nicolás");
+        assert_eq!(repair_mac_roman("Nicolás"), "Nicolás");
+        assert_eq!(repair_mac_roman("Lee"), "Lee");
+        assert_eq!(normalize_name("Nicol√°s  Acevedo"), "nicolas acevedo");
+    }
+
+    #[test]
+    fn accents_fold_and_punctuation_separates_words() {
+        assert_eq!(normalize_name("Nicolás Acevedo"), "nicolas acevedo");
+        assert_eq!(normalize_name("Thomas Müller"), "thomas muller");
+        assert_eq!(normalize_name("Łukasz Żmuda-Szałas"), "lukasz zmuda szalas");
+        assert_eq!(normalize_name("  D'Angelo O.  Smith "), "d angelo o smith");
+        assert_eq!(normalize_name("Sæbø"), "saebo");
+    }
+
+    #[test]
+    fn similarity_is_one_minus_the_relative_edit_distance() {
+        assert_eq!(similarity("ann lee", "ann lee"), 1.0);
+        assert_eq!(similarity("", ""), 1.0);
+        assert_eq!(similarity("abc", ""), 0.0);
+        assert_eq!(similarity("ann lee", "anne lee"), 1.0 - 1.0 / 8.0);
+        assert_eq!(similarity("kitten", "sitting"), 1.0 - 3.0 / 7.0);
+    }
+}
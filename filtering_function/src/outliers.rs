@@ -0,0 +1,345 @@
+// Salary outlier detection relative to a player's team, position or the
+// whole league.
+//
+// Each group gets a baseline (a center, a spread and the salary fences that
+// follow from the threshold) and every player whose score falls outside the
+// threshold is reported together with the baseline it was compared against.
+//  - zscore: (salary - mean) / standard deviation
+//  - iqr:    distance past the nearest quartile in IQRs (Tukey fences)
+//  - mad:    modified z-score, 0.6745 * (salary - median) / MAD
+
+use crate::salaries_by;
+use crate::top_n::Grouping;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+// Group key used when comparing against the whole league.
+const LEAGUE_GROUP: &str = "all";
+
+// Scales the MAD so it estimates the standard deviation of normal data.
+const MAD_CONSISTENCY: f64 = 0.6745;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    ZScore,
+    Iqr,
+    #[default]
+    Mad,
+}
+
+impl Method {
+    fn scale(self) -> f64 {
+        match self {
+            Method::Mad => MAD_CONSISTENCY,
+            Method::ZScore | Method::Iqr => 1.0,
+        }
+    }
+
+    // The conventional cut-off for each method.
+    pub fn default_threshold(self) -> f64 {
+        match self {
+            Method::ZScore => 3.0,
+            Method::Iqr => 1.5,
+            Method::Mad => 3.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct Baseline {
+    pub group: String,
+    pub method: Method,
+    pub players: usize,
+    pub center: f64,
+    pub spread: f64,
+    pub lower_fence: f64,
+    pub upper_fence: f64,
+    // Points scores are measured from: the mean, the quartiles or the median.
+    #[serde(skip)]
+    anchors: (f64, f64),
+}
+
+impl Baseline {
+    // Returns `None` when the group has no spread, since every score would
+    // then be undefined.
+    fn new(group: String, method: Method, threshold: f64, mut salaries: Vec<f64>) -> Option<Self> {
+        salaries.sort_by(f64::total_cmp);
+        let median = quantile(&salaries, 0.5);
+        let (center, spread, anchors) = match method {
+            Method::ZScore => {
+                let mean = mean(&salaries);
+                (mean, std_dev(&salaries, mean), (mean, mean))
+            }
+            Method::Iqr => {
+                let (q1, q3) = (quantile(&salaries, 0.25), quantile(&salaries, 0.75));
+                (median, q3 - q1, (q1, q3))
+            }
+            Method::Mad => {
+                let mut deviations: Vec<f64> = salaries
+                    .iter()
+                    .map(|salary| (salary - median).abs())
+                    .collect();
+                deviations.sort_by(f64::total_cmp);
+                (median, quantile(&deviations, 0.5), (median, median))
+            }
+        };
+        if spread <= 0.0 {
+            return None;
+        }
+
+        let reach = threshold * spread / method.scale();
+        Some(Baseline {
+            group,
+            method,
+            players: salaries.len(),
+            center,
+            spread,
+            lower_fence: anchors.0 - reach,
+            upper_fence: anchors.1 + reach,
+            anchors,
+        })
+    }
+
+    // Signed distance from the nearest anchor in (scaled) spreads.
+    fn score(&self, salary: f64) -> f64 {
+        let (low, high) = self.anchors;
+        let distance = if salary > high {
+            salary - high
+        } else if salary < low {
+            salary - low
+        } else {
+            0.0
+        };
+        self.method.scale() * distance / self.spread
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct Outlier {
+    pub first_name: String,
+    pub last_name: String,
+    pub team: String,
+    pub position: String,
+    pub salary: f64,
+    pub score: f64,
+    pub baseline: Baseline,
+}
+
+// Flags the players whose salary score exceeds `threshold` in absolute value
+// within their group, sorted by descending absolute score. `threshold`
+// defaults to the method's conventional cut-off.
+pub fn outliers(
+    df: &DataFrame,
+    grouping: Grouping,
+    method: Method,
+    threshold: Option<f64>,
+) -> Result<Vec<Outlier>, PolarsError> {
+    let threshold = threshold.unwrap_or_else(|| method.default_threshold());
+    let mut df = df.clone();
+    let group_column = match grouping.column() {
+        Some(column) => column,
+        None => {
+            df.with_column(Series::new("group", vec![LEAGUE_GROUP; df.height()]))?;
+            "group"
+        }
+    };
+
+    let baselines: BTreeMap<String, Baseline> = salaries_by(&df, group_column)?
+        .into_iter()
+        .filter_map(|(group, salaries)| {
+            Baseline::new(group.clone(), method, threshold, salaries).map(|b| (group, b))
+        })
+        .collect();
+
+    let first_names = df.column("first_name")?.utf8()?;
+    let last_names = df.column("last_name")?.utf8()?;
+    let teams = df.column("team")?.utf8()?;
+    let positions = df.column("position")?.utf8()?;
+    let salaries = df.column("salary")?.f64()?;
+    let groups = df.column(group_column)?.utf8()?;
+
+    let mut flagged = Vec::new();
+    for i in 0..df.height() {
+        let (Some(group), Some(salary)) = (groups.get(i), salaries.get(i)) else {
+            continue;
+        };
+        let Some(baseline) = baselines.get(group) else {
+            continue;
+        };
+        let score = baseline.score(salary);
+        if score.abs() > threshold {
+            flagged.push(Outlier {
+                first_name: first_names.get(i).unwrap_or_default().to_string(),
+                last_name: last_names.get(i).unwrap_or_default().to_string(),
+                team: teams.get(i).unwrap_or_default().to_string(),
+                position: positions.get(i).unwrap_or_default().to_string(),
+                salary,
+                score,
+                baseline: baseline.clone(),
+            });
+        }
+    }
+    flagged.sort_by(|a, b| b.score.abs().total_cmp(&a.score.abs()));
+    Ok(flagged)
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// Sample standard deviation (n - 1).
+fn std_dev(values: &[f64], mean: f64) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let squares: f64 = values.iter().map(|value| (value - mean).powi(2)).sum();
+    (squares / (values.len() - 1) as f64).sqrt()
+}
+
+// Linearly interpolated quantile of ascending `sorted` values.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let (low, high) = (position.floor() as usize, position.ceil() as usize);
+    sorted[low] + (sorted[high] - sorted[low]) * (position - low as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    // Team A has one runaway salary; team B shares one salary but for a
+    // single player, and team C all earn the same.
+    fn frame() -> DataFrame {
+        let a = [10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0, 17.0, 100.0];
+        let b = [5.0, 5.0, 5.0, 5.0, 9.0];
+        let c = [7.0, 7.0, 7.0];
+        let mut teams = Vec::new();
+        let mut salaries = Vec::new();
+        for (team, group) in [("A", &a[..]), ("B", &b[..]), ("C", &c[..])] {
+            teams.extend(std::iter::repeat_n(team, group.len()));
+            salaries.extend_from_slice(group);
+        }
+        let names: Vec<String> = (0..salaries.len()).map(|i| format!("P{}", i)).collect();
+        df!(
+            "first_name" => names.clone(),
+            "last_name" => names,
+            "team" => teams,
+            "position" => vec!["M"; salaries.len()],
+            "salary" => salaries,
+        )
+        .unwrap()
+    }
+
+    fn baseline(method: Method, threshold: f64, salaries: &[f64]) -> Option<Baseline> {
+        Baseline::new("A".to_string(), method, threshold, salaries.to_vec())
+    }
+
+    #[test]
+    fn default_thresholds() {
+        assert_eq!(Method::ZScore.default_threshold(), 3.0);
+        assert_eq!(Method::Iqr.default_threshold(), 1.5);
+        assert_eq!(Method::Mad.default_threshold(), 3.5);
+        assert_eq!(Method::default(), Method::Mad);
+    }
+
+    #[test]
+    fn zscore_fences_are_mean_plus_or_minus_deviations() {
+        let salaries = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let baseline = baseline(Method::ZScore, 2.0, &salaries).unwrap();
+        // Mean 5, sample variance 32 / 7.
+        let sd = (32.0_f64 / 7.0).sqrt();
+        assert!(close(baseline.center, 5.0));
+        assert!(close(baseline.spread, sd));
+        assert!(close(baseline.lower_fence, 5.0 - 2.0 * sd));
+        assert!(close(baseline.upper_fence, 5.0 + 2.0 * sd));
+        assert!(close(baseline.score(9.0), 4.0 / sd));
+        assert!(close(baseline.score(2.0), -3.0 / sd));
+    }
+
+    #[test]
+    fn iqr_fences_are_tukey_fences() {
+        let salaries = [10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0, 17.0, 100.0];
+        let baseline = baseline(Method::Iqr, 1.5, &salaries).unwrap();
+        // Quartiles 12 and 16.
+        assert_eq!((baseline.center, baseline.spread), (14.0, 4.0));
+        assert_eq!((baseline.lower_fence, baseline.upper_fence), (6.0, 22.0));
+        // Scores count IQRs past the nearest quartile, zero in between.
+        assert_eq!(baseline.score(100.0), 21.0);
+        assert_eq!(baseline.score(8.0), -1.0);
+        assert_eq!(baseline.score(13.0), 0.0);
+    }
+
+    #[test]
+    fn mad_fences_are_scaled_median_deviations() {
+        let salaries = [10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0, 17.0, 100.0];
+        let baseline = baseline(Method::Mad, 3.5, &salaries).unwrap();
+        // Median 14; deviations 0, 1, 1, 2, 2, 3, 3, 4, 86 give a MAD of 2.
+        assert_eq!((baseline.center, baseline.spread), (14.0, 2.0));
+        let reach = 3.5 * 2.0 / MAD_CONSISTENCY;
+        assert!(close(baseline.lower_fence, 14.0 - reach));
+        assert!(close(baseline.upper_fence, 14.0 + reach));
+        assert!(close(baseline.score(100.0), MAD_CONSISTENCY * 86.0 / 2.0));
+    }
+
+    #[test]
+    fn groups_without_spread_have_no_baseline() {
+        for method in [Method::ZScore, Method::Iqr, Method::Mad] {
+            assert_eq!(baseline(method, 1.0, &[7.0, 7.0, 7.0]), None);
+            assert_eq!(baseline(method, 1.0, &[7.0]), None);
+        }
+        // Most players share a salary, so the MAD and IQR are zero even
+        // though the salaries differ.
+        assert_eq!(baseline(Method::Mad, 1.0, &[5.0, 5.0, 5.0, 5.0, 9.0]), None);
+        assert_eq!(baseline(Method::Iqr, 1.0, &[5.0, 5.0, 5.0, 5.0, 9.0]), None);
+    }
+
+    #[test]
+    fn flags_players_past_the_default_fences() {
+        let df = frame();
+        for method in [Method::Iqr, Method::Mad] {
+            let flagged = outliers(&df, Grouping::Team, method, None).unwrap();
+            let salaries: Vec<f64> = flagged.iter().map(|o| o.salary).collect();
+            // Team B's 9 is not flagged: a zero spread gives no baseline.
+            assert_eq!(salaries, vec![100.0], "{:?}", method);
+            assert_eq!(flagged[0].team, "A");
+            assert_eq!(flagged[0].baseline.method, method);
+            assert_eq!(flagged[0].baseline.players, 9);
+        }
+        // With nine players no z-score can reach 3 (the most is 8 / 3), so
+        // the default flags nobody, while a looser cut-off flags the 100.
+        assert!(outliers(&df, Grouping::Team, Method::ZScore, None)
+            .unwrap()
+            .is_empty());
+        let flagged = outliers(&df, Grouping::Team, Method::ZScore, Some(2.0)).unwrap();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].salary, 100.0);
+    }
+
+    #[test]
+    fn zero_spread_groups_flag_no_one() {
+        let df = frame();
+        // Even a zero threshold leaves team C alone.
+        for method in [Method::ZScore, Method::Iqr, Method::Mad] {
+            let flagged = outliers(&df, Grouping::Team, method, Some(0.0)).unwrap();
+            assert!(flagged.iter().all(|o| o.team != "C"), "{:?}", method);
+        }
+    }
+
+    #[test]
+    fn sorted_by_absolute_score_across_the_league() {
+        let flagged = outliers(&frame(), Grouping::League, Method::Iqr, Some(0.0)).unwrap();
+        assert!(!flagged.is_empty());
+        assert!(flagged
+            .windows(2)
+            .all(|pair| pair[0].score.abs() >= pair[1].score.abs()));
+        assert!(flagged.iter().all(|o| o.baseline.group == LEAGUE_GROUP));
+        assert_eq!(flagged[0].salary, 100.0);
+    }
+}
@@ -0,0 +1,186 @@
+// Server-rendered HTML pages for browsing the salaries without an API
+// client.
+//
+// `/` shows a threshold form and the per-team count of players earning more
+// than the threshold (the `count_above` table); every team links to its own
+// page listing the roster by salary. Pages are plain HTML with inline CSS so
+// they work from the Lambda URL with no static assets.
+
+use crate::count_above;
+use crate::names::repair_mac_roman;
+use polars::prelude::*;
+
+pub const DEFAULT_THRESHOLD: f64 = 1_000_000.0;
+
+const STYLE: &str =
+    "body{font-family:system-ui,sans-serif;margin:2rem auto;max-width:48rem;color:#222}\
+table{border-collapse:collapse;width:100%}\
+th,td{padding:.3rem .6rem;border-bottom:1px solid #ddd;text-align:left}\
+td.num,th.num{text-align:right}\
+tr.above{background:#fff4d6}\
+form{margin:1rem 0}";
+
+// Escapes text for element content and quoted attribute values.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Percent-encodes a path segment.
+fn encode_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        title = escape(title),
+    )
+}
+
+fn threshold_form(action: &str, threshold: f64) -> String {
+    format!(
+        "<form method=\"get\" action=\"{}\">\n\
+         <label>Salary above <input type=\"number\" name=\"threshold\" min=\"0\" step=\"any\" value=\"{}\"></label>\n\
+         <button type=\"submit\">Update</button>\n</form>\n",
+        escape(action),
+        threshold
+    )
+}
+
+fn dollars(amount: f64) -> String {
+    let whole = format!("{:.0}", amount.abs());
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    let sign = if amount < 0.0 { "-" } else { "" };
+    format!("{}${}", sign, grouped)
+}
+
+// The landing page: threshold form and players above it per team.
+pub fn index_page(df: &DataFrame, threshold: f64) -> Result<String, PolarsError> {
+    let counts =
+        count_above(df, threshold)?.sort(["position", "team"], vec![true, false], false)?;
+    let teams = counts.column("team")?.utf8()?;
+    let players = counts.column("position")?.cast(&DataType::UInt32)?;
+
+    let mut rows = String::new();
+    for (team, players) in teams.into_iter().zip(players.u32()?) {
+        let team = team.unwrap_or_default();
+        rows.push_str(&format!(
+            "<tr><td><a href=\"/teams/{}?threshold={}\">{}</a></td><td class=\"num\">{}</td></tr>\n",
+            encode_segment(team),
+            threshold,
+            escape(team),
+            players.unwrap_or_default()
+        ));
+    }
+    if rows.is_empty() {
+        rows.push_str("<tr><td colspan=\"2\">No player earns more than this.</td></tr>\n");
+    }
+
+    let body = format!(
+        "<h1>Team salary breakdown</h1>\n{}\
+         <p>Players earning more than {} per team.</p>\n\
+         <table>\n<thead><tr><th>Team</th><th class=\"num\">Players</th></tr></thead>\n\
+         <tbody>\n{}</tbody>\n</table>\n",
+        threshold_form("/", threshold),
+        dollars(threshold),
+        rows
+    );
+    Ok(page("Team salary breakdown", &body))
+}
+
+// A team's roster by salary, highlighting players above the threshold.
+// Returns None for a team with no players.
+pub fn team_page(
+    df: &DataFrame,
+    team: &str,
+    threshold: f64,
+) -> Result<Option<String>, PolarsError> {
+    let roster = df
+        .clone()
+        .lazy()
+        .filter(col("team").eq(lit(team)))
+        .sort(
+            "salary",
+            SortOptions {
+                descending: true,
+                ..Default::default()
+            },
+        )
+        .collect()?;
+    if roster.height() == 0 {
+        return Ok(None);
+    }
+
+    let first_names = roster.column("first_name")?.utf8()?;
+    let last_names = roster.column("last_name")?.utf8()?;
+    let positions = roster.column("position")?.utf8()?;
+    let salaries = roster.column("salary")?.cast(&DataType::Float64)?;
+    let mut rows = String::new();
+    let mut above = 0;
+    let mut payroll = 0.0;
+    for (((first, last), position), salary) in first_names
+        .into_iter()
+        .zip(last_names)
+        .zip(positions)
+        .zip(salaries.f64()?)
+    {
+        let salary = salary.unwrap_or_default();
+        let class = if salary > threshold {
+            above += 1;
+            " class=\"above\""
+        } else {
+            ""
+        };
+        payroll += salary;
+        rows.push_str(&format!(
+            "<tr{}><td>{} {}</td><td>{}</td><td class=\"num\">{}</td></tr>\n",
+            class,
+            escape(&repair_mac_roman(first.unwrap_or_default())),
+            escape(&repair_mac_roman(last.unwrap_or_default())),
+            escape(position.unwrap_or_default()),
+            dollars(salary)
+        ));
+    }
+
+    let body = format!(
+        "<p><a href=\"/?threshold={threshold}\">&larr; All teams</a></p>\n\
+         <h1>{team}</h1>\n{form}\
+         <p>{above} of {players} players earn more than {limit}. Payroll {payroll}.</p>\n\
+         <table>\n<thead><tr><th>Player</th><th>Position</th><th class=\"num\">Salary</th></tr></thead>\n\
+         <tbody>\n{rows}</tbody>\n</table>\n",
+        team = escape(team),
+        form = threshold_form(&format!("/teams/{}", encode_segment(team)), threshold),
+        players = roster.height(),
+        limit = dollars(threshold),
+        payroll = dollars(payroll),
+    );
+    Ok(Some(page(team, &body)))
+}
@@ -0,0 +1,343 @@
+// Cache of computed result frames.
+//
+// Handlers look results up by a normalized query (the operation and its
+// parsed parameters, e.g. `count_above?value=800000`) under the league and
+// the version of its data. Entries live for RESULT_CACHE_TTL_SECS (300 by
+// default); past RESULT_CACHE_ENTRIES (256) entries or RESULT_CACHE_BYTES
+// (64 MiB) of frames the least recently used go first. Reloading a league
+// drops its entries, and entries of an older version are never served.
+
+use polars::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+pub struct Limits {
+    pub max_entries: usize,
+    pub max_bytes: usize,
+    pub ttl_secs: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_entries: 256,
+            max_bytes: 64 << 20,
+            ttl_secs: 300,
+        }
+    }
+}
+
+impl Limits {
+    // The defaults, overridden by RESULT_CACHE_ENTRIES, RESULT_CACHE_BYTES
+    // and RESULT_CACHE_TTL_SECS.
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str, default: T) -> T {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(default)
+        }
+        let defaults = Limits::default();
+        Limits {
+            max_entries: var("RESULT_CACHE_ENTRIES", defaults.max_entries),
+            max_bytes: var("RESULT_CACHE_BYTES", defaults.max_bytes),
+            ttl_secs: var("RESULT_CACHE_TTL_SECS", defaults.ttl_secs),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, ToSchema)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+    // Entries dropped for the size limits.
+    pub evictions: u64,
+    // Entries dropped for the TTL.
+    pub expirations: u64,
+    // Entries dropped because their league was reloaded or changed version.
+    pub invalidations: u64,
+    pub entries: usize,
+    pub bytes: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+pub struct Diagnostics {
+    pub limits: Limits,
+    pub stats: Stats,
+    // hits / (hits + misses), None before the first lookup.
+    pub hit_ratio: Option<f64>,
+}
+
+struct Entry {
+    df: DataFrame,
+    version: String,
+    bytes: usize,
+    inserted: Instant,
+    // `ResultCache::clock` at the last hit, for LRU eviction.
+    used: u64,
+}
+
+pub struct ResultCache {
+    limits: Limits,
+    // (league, query) to its result.
+    entries: HashMap<(String, String), Entry>,
+    clock: u64,
+    stats: Stats,
+}
+
+impl ResultCache {
+    pub fn new(limits: Limits) -> Self {
+        ResultCache {
+            limits,
+            entries: HashMap::new(),
+            clock: 0,
+            stats: Stats::default(),
+        }
+    }
+
+    fn remove(&mut self, key: &(String, String)) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.stats.bytes -= entry.bytes;
+        }
+    }
+
+    // The result of `query` on `league`'s data at `version`, if cached and
+    // fresh at `now`.
+    pub fn get(
+        &mut self,
+        league: &str,
+        version: &str,
+        query: &str,
+        now: Instant,
+    ) -> Option<DataFrame> {
+        self.clock += 1;
+        let key = (league.to_string(), query.to_string());
+        let ttl = Duration::from_secs(self.limits.ttl_secs);
+        let Some(entry) = self.entries.get_mut(&key) else {
+            self.stats.misses += 1;
+            return None;
+        };
+        if entry.version != version {
+            self.stats.invalidations += 1;
+        } else if now.duration_since(entry.inserted) >= ttl {
+            self.stats.expirations += 1;
+        } else {
+            entry.used = self.clock;
+            self.stats.hits += 1;
+            return Some(entry.df.clone());
+        }
+        self.remove(&key);
+        self.stats.misses += 1;
+        None
+    }
+
+    pub fn insert(
+        &mut self,
+        league: &str,
+        version: &str,
+        query: &str,
+        df: DataFrame,
+        now: Instant,
+    ) {
+        let bytes = df.estimated_size();
+        let key = (league.to_string(), query.to_string());
+        self.remove(&key);
+        if bytes > self.limits.max_bytes || self.limits.max_entries == 0 {
+            return;
+        }
+        while self.entries.len() >= self.limits.max_entries
+            || self.stats.bytes + bytes > self.limits.max_bytes
+        {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+        self.clock += 1;
+        self.stats.bytes += bytes;
+        self.entries.insert(
+            key,
+            Entry {
+                df,
+                version: version.to_string(),
+                bytes,
+                inserted: now,
+                used: self.clock,
+            },
+        );
+    }
+
+    // Drops every entry of `league`.
+    pub fn invalidate(&mut self, league: &str) {
+        let keys: Vec<_> = self
+            .entries
+            .keys()
+            .filter(|(id, _)| id == league)
+            .cloned()
+            .collect();
+        self.stats.invalidations += keys.len() as u64;
+        for key in keys {
+            self.remove(&key);
+        }
+    }
+
+    pub fn diagnostics(&self) -> Diagnostics {
+        let stats = Stats {
+            entries: self.entries.len(),
+            ..self.stats
+        };
+        let lookups = stats.hits + stats.misses;
+        Diagnostics {
+            limits: self.limits,
+            stats,
+            hit_ratio: (lookups > 0).then(|| stats.hits as f64 / lookups as f64),
+        }
+    }
+}
+
+// The process-wide cache, with `Limits::from_env`.
+pub fn results() -> &'static Mutex<ResultCache> {
+    static RESULTS: OnceLock<Mutex<ResultCache>> = OnceLock::new();
+    RESULTS.get_or_init(|| Mutex::new(ResultCache::new(Limits::from_env())))
+}
+
+// `league`'s result for `query` at `version`, computed by `compute` on a
+// miss. Returns whether it was a hit alongside the frame.
+pub fn cached(
+    league: &str,
+    version: &str,
+    query: &str,
+    compute: impl FnOnce() -> Result<DataFrame, PolarsError>,
+) -> Result<(DataFrame, bool), PolarsError> {
+    if let Some(df) = results()
+        .lock()
+        .unwrap()
+        .get(league, version, query, Instant::now())
+    {
+        return Ok((df, true));
+    }
+    // Computed without the lock so slow queries don't hold up others.
+    let df = compute()?;
+    results()
+        .lock()
+        .unwrap()
+        .insert(league, version, query, df.clone(), Instant::now());
+    Ok((df, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(rows: usize) -> DataFrame {
+        df!("salary" => vec![1.0; rows]).unwrap()
+    }
+
+    fn cache(max_entries: usize, max_bytes: usize) -> ResultCache {
+        ResultCache::new(Limits {
+            max_entries,
+            max_bytes,
+            ttl_secs: 60,
+        })
+    }
+
+    fn hit(cache: &mut ResultCache, query: &str, now: Instant) -> bool {
+        cache.get("mls", "v1", query, now).is_some()
+    }
+
+    #[test]
+    fn entries_expire_after_the_ttl() {
+        let mut cache = cache(8, 1 << 20);
+        let start = Instant::now();
+        cache.insert("mls", "v1", "a", frame(1), start);
+        assert!(hit(&mut cache, "a", start + Duration::from_secs(59)));
+        assert!(!hit(&mut cache, "a", start + Duration::from_secs(60)));
+
+        let stats = cache.diagnostics().stats;
+        assert_eq!((stats.hits, stats.misses, stats.expirations), (1, 1, 1));
+        assert_eq!((stats.entries, stats.bytes), (0, 0));
+    }
+
+    #[test]
+    fn the_least_recently_used_entry_goes_first() {
+        let mut cache = cache(2, 1 << 20);
+        let now = Instant::now();
+        cache.insert("mls", "v1", "a", frame(1), now);
+        cache.insert("mls", "v1", "b", frame(1), now);
+        // "a" is now more recent than "b".
+        assert!(hit(&mut cache, "a", now));
+        cache.insert("mls", "v1", "c", frame(1), now);
+
+        assert!(hit(&mut cache, "a", now));
+        assert!(!hit(&mut cache, "b", now));
+        assert!(hit(&mut cache, "c", now));
+        assert_eq!(cache.diagnostics().stats.evictions, 1);
+    }
+
+    #[test]
+    fn entries_are_evicted_to_stay_within_the_byte_limit() {
+        let size = frame(1000).estimated_size();
+        let mut cache = cache(100, 2 * size + size / 2);
+        let now = Instant::now();
+        for query in ["a", "b", "c"] {
+            cache.insert("mls", "v1", query, frame(1000), now);
+        }
+        assert!(!hit(&mut cache, "a", now));
+        assert!(hit(&mut cache, "b", now));
+        assert!(hit(&mut cache, "c", now));
+        let stats = cache.diagnostics().stats;
+        assert_eq!(
+            (stats.entries, stats.bytes, stats.evictions),
+            (2, 2 * size, 1)
+        );
+
+        // Frames larger than the whole cache are not kept at all.
+        cache.insert("mls", "v1", "huge", frame(10_000), now);
+        assert!(!hit(&mut cache, "huge", now));
+        assert_eq!(cache.diagnostics().stats.entries, 2);
+    }
+
+    #[test]
+    fn entries_of_another_version_are_dropped() {
+        let mut cache = cache(8, 1 << 20);
+        let now = Instant::now();
+        cache.insert("mls", "v1", "a", frame(1), now);
+        assert!(cache.get("mls", "v2", "a", now).is_none());
+        // Dropped, not just skipped.
+        assert!(!hit(&mut cache, "a", now));
+
+        let stats = cache.diagnostics().stats;
+        assert_eq!(
+            (stats.invalidations, stats.misses, stats.entries),
+            (1, 2, 0)
+        );
+    }
+
+    #[test]
+    fn invalidate_drops_only_that_league() {
+        let mut cache = cache(8, 1 << 20);
+        let now = Instant::now();
+        cache.insert("mls", "v1", "a", frame(1), now);
+        cache.insert("mls", "v1", "b", frame(1), now);
+        cache.insert("nwsl", "v1", "a", frame(1), now);
+        cache.invalidate("mls");
+
+        assert!(!hit(&mut cache, "a", now));
+        assert!(!hit(&mut cache, "b", now));
+        assert!(cache.get("nwsl", "v1", "a", now).is_some());
+        let diagnostics = cache.diagnostics();
+        assert_eq!(diagnostics.stats.invalidations, 2);
+        assert_eq!(diagnostics.stats.entries, 1);
+        assert_eq!(diagnostics.hit_ratio, Some(1.0 / 3.0));
+    }
+}
@@ -0,0 +1,548 @@
+// Budget-constrained roster builder.
+//
+// Picks the set of players that maximises the total value score while
+// filling every position quota exactly and keeping total salary within the
+// budget. A hybrid position such as `D-M` is eligible for either of its
+// parts. Without user-supplied scores, a player's value is their salary.
+//
+// This is a 0/1 knapsack solved by dynamic programming over (players picked
+// per position, budget spent). Salaries are rounded up to a budget step so
+// every returned roster really fits the budget; the step is chosen so there
+// are at most `MAX_BUDGET_STEPS` of them. Each state keeps its best
+// `alternatives + 1` partial rosters so runners-up can be returned too.
+//
+// The table has one entry per (count per position, budget step), so
+// requests are bounded: quotas add up to at most `MAX_SQUAD` players, at
+// most `MAX_ALTERNATIVES` runners-up are kept and the table holds at most
+// `MAX_STATES` entries. Requests beyond that are rejected.
+
+use crate::{players, Player};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use utoipa::ToSchema;
+
+const MAX_BUDGET_STEPS: f64 = 1_000.0;
+pub const MAX_SQUAD: usize = 30;
+pub const MAX_ALTERNATIVES: usize = 10;
+pub const MAX_STATES: usize = 2_000_000;
+
+fn default_quotas() -> BTreeMap<String, usize> {
+    [("GK", 1), ("D", 4), ("M", 4), ("F", 2)]
+        .into_iter()
+        .map(|(position, count)| (position.to_string(), count))
+        .collect()
+}
+
+fn default_alternatives() -> usize {
+    2
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct RosterRequest {
+    pub budget: f64,
+    // Players needed per position, 1 GK, 4 D, 4 M and 2 F by default.
+    #[serde(default = "default_quotas")]
+    pub quotas: BTreeMap<String, usize>,
+    // Value score per player keyed by "first_name last_name". When given,
+    // players missing from the map are worth nothing.
+    pub values: Option<HashMap<String, f64>>,
+    #[serde(default = "default_alternatives")]
+    pub alternatives: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RosterPick {
+    #[serde(flatten)]
+    pub player: Player,
+    // The quota position the player fills.
+    pub slot: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct Roster {
+    pub total_salary: f64,
+    pub total_value: f64,
+    pub players: Vec<RosterPick>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RosterResult {
+    // `None` when no combination fills the quotas within the budget.
+    pub best: Option<Roster>,
+    pub alternatives: Vec<Roster>,
+}
+
+// A persistent list of (candidate, slot) picks shared between states.
+struct Pick {
+    candidate: usize,
+    slot: usize,
+    previous: Option<Rc<Pick>>,
+}
+
+#[derive(Clone)]
+struct Partial {
+    value: f64,
+    picks: Option<Rc<Pick>>,
+}
+
+struct Candidate {
+    player: Player,
+    value: f64,
+    cost: usize,
+    slots: Vec<usize>,
+}
+
+fn invalid(message: impl Into<String>) -> PolarsError {
+    PolarsError::ComputeError(message.into().into())
+}
+
+impl RosterRequest {
+    // Checks the request is within the limits above.
+    pub fn validate(&self) -> Result<(), PolarsError> {
+        if !self.budget.is_finite() || self.budget < 0.0 {
+            return Err(invalid("budget must be a non-negative number"));
+        }
+        let squad = self
+            .quotas
+            .values()
+            .try_fold(0usize, |total, count| total.checked_add(*count));
+        if squad.is_none_or(|squad| squad > MAX_SQUAD) {
+            return Err(invalid(format!(
+                "quotas ask for more than {} players",
+                MAX_SQUAD
+            )));
+        }
+        if self.alternatives > MAX_ALTERNATIVES {
+            return Err(invalid(format!(
+                "at most {} alternatives can be requested",
+                MAX_ALTERNATIVES
+            )));
+        }
+        Ok(())
+    }
+}
+
+// The best roster for `request`, or an error for requests outside the
+// limits.
+pub fn build_roster(df: &DataFrame, request: &RosterRequest) -> Result<RosterResult, PolarsError> {
+    request.validate()?;
+    let slots: Vec<(&String, usize)> = request
+        .quotas
+        .iter()
+        .filter(|(_, count)| **count > 0)
+        .map(|(position, count)| (position, *count))
+        .collect();
+    let keep = request.alternatives + 1;
+    let step = (request.budget / MAX_BUDGET_STEPS).max(1.0);
+    let budget_steps = (request.budget / step).floor().max(0.0) as usize;
+
+    let candidates: Vec<Candidate> = players(df)?
+        .into_iter()
+        .filter(|player| player.salary <= request.budget)
+        .filter_map(|player| {
+            let eligible: Vec<usize> = player
+                .position
+                .split('-')
+                .filter_map(|part| slots.iter().position(|(position, _)| *position == part))
+                .collect();
+            if eligible.is_empty() {
+                return None;
+            }
+            let value = match &request.values {
+                Some(values) => {
+                    let name = format!("{} {}", player.first_name, player.last_name);
+                    values.get(&name).copied().unwrap_or(0.0)
+                }
+                None => player.salary,
+            };
+            Some(Candidate {
+                cost: (player.salary / step).ceil() as usize,
+                value,
+                slots: eligible,
+                player,
+            })
+        })
+        .collect();
+
+    let candidates = prune(candidates, &slots, request.alternatives);
+
+    // States are laid out as [count of slot 0][count of slot 1]...[budget
+    // step], so picking a player always moves to a higher index and a single
+    // descending sweep per candidate never uses the same candidate twice.
+    let mut strides = vec![0; slots.len()];
+    let mut stride = Some(budget_steps + 1);
+    for (i, (_, count)) in slots.iter().enumerate().rev() {
+        strides[i] = stride.unwrap_or(0);
+        stride = stride.and_then(|stride| stride.checked_mul(count + 1));
+    }
+    let states = stride
+        .filter(|states| *states <= MAX_STATES)
+        .ok_or_else(|| {
+            invalid("too many quota and budget combinations, lower the quotas or the budget")
+        })?;
+
+    let mut table: Vec<Vec<Partial>> = vec![Vec::new(); states];
+    table[0].push(Partial {
+        value: 0.0,
+        picks: None,
+    });
+
+    for (c, candidate) in candidates.iter().enumerate() {
+        for state in (0..states).rev() {
+            let spent = state % (budget_steps + 1);
+            if candidate.cost > spent {
+                continue;
+            }
+            for &slot in &candidate.slots {
+                let filled = (state / strides[slot]) % (slots[slot].1 + 1);
+                if filled == 0 {
+                    continue;
+                }
+                let source = state - strides[slot] - candidate.cost;
+                // Source and target never alias since `source < state`.
+                let (lower, upper) = table.split_at_mut(state);
+                for partial in &lower[source] {
+                    let extended = Partial {
+                        value: partial.value + candidate.value,
+                        picks: partial.picks.clone(),
+                    };
+                    // Sources are sorted, so once one does not make the cut
+                    // none of the following will.
+                    if !insert(&mut upper[0], extended, keep, |picks| {
+                        Some(Rc::new(Pick {
+                            candidate: c,
+                            slot,
+                            previous: picks,
+                        }))
+                    }) {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    // Full rosters are the states with every quota filled, at any spend.
+    let full: usize = slots
+        .iter()
+        .enumerate()
+        .map(|(i, (_, count))| count * strides[i])
+        .sum();
+    let mut best = Vec::new();
+    for spent in 0..=budget_steps {
+        for partial in &table[full + spent] {
+            if !insert(&mut best, partial.clone(), keep, |picks| picks) {
+                break;
+            }
+        }
+    }
+
+    let mut rosters = Vec::new();
+    for partial in best {
+        let mut picks = Vec::new();
+        let mut next = partial.picks;
+        while let Some(pick) = next {
+            picks.push((pick.candidate, pick.slot));
+            next = pick.previous.clone();
+        }
+        picks.sort_by_key(|&(candidate, slot)| (slot, candidate));
+
+        let players: Vec<RosterPick> = picks
+            .into_iter()
+            .map(|(candidate, slot)| RosterPick {
+                player: candidates[candidate].player.clone(),
+                slot: slots[slot].0.clone(),
+                value: candidates[candidate].value,
+            })
+            .collect();
+        rosters.push(Roster {
+            total_salary: players.iter().map(|pick| pick.player.salary).sum(),
+            total_value: partial.value,
+            players,
+        });
+    }
+
+    let mut rosters = rosters.into_iter();
+    Ok(RosterResult {
+        best: rosters.next(),
+        alternatives: rosters.collect(),
+    })
+}
+
+// Drops candidates that cannot appear in any of the best rosters: a player
+// is dominated when, among players eligible for exactly the same slots, at
+// least as many as those slots can hold (plus the number of alternatives)
+// cost no more and are worth at least as much. Swapping the player for a
+// dominator that is not picked never makes a roster worse.
+fn prune(
+    mut candidates: Vec<Candidate>,
+    slots: &[(&String, usize)],
+    alternatives: usize,
+) -> Vec<Candidate> {
+    candidates.sort_by(|a, b| {
+        a.slots
+            .cmp(&b.slots)
+            .then(b.value.total_cmp(&a.value))
+            .then(a.cost.cmp(&b.cost))
+    });
+    let mut kept: Vec<Candidate> = Vec::new();
+    let mut class_start = 0;
+    for candidate in candidates {
+        if kept
+            .get(class_start)
+            .is_none_or(|first| first.slots != candidate.slots)
+        {
+            class_start = kept.len();
+        }
+        let limit: usize = candidate
+            .slots
+            .iter()
+            .map(|&slot| slots[slot].1)
+            .sum::<usize>()
+            + alternatives;
+        // Everything kept so far in this class is worth at least as much.
+        let dominators = kept[class_start..]
+            .iter()
+            .filter(|other| other.cost <= candidate.cost)
+            .count();
+        if dominators < limit {
+            kept.push(candidate);
+        }
+    }
+    kept
+}
+
+// The candidates picked, sorted.
+fn chosen(picks: &Option<Rc<Pick>>) -> Vec<usize> {
+    let mut chosen = Vec::new();
+    let mut next = picks.as_ref();
+    while let Some(pick) = next {
+        chosen.push(pick.candidate);
+        next = pick.previous.as_ref();
+    }
+    chosen.sort();
+    chosen
+}
+
+// Inserts `partial` into the descending `into` if it is among the `keep`
+// best, completing its pick list with `link` only once it is kept. Returns
+// whether it was kept, or whether it would have been but `into` already
+// holds the same players: hybrids can reach a state in different slots,
+// and only the first assignment of a set counts.
+fn insert(
+    into: &mut Vec<Partial>,
+    mut partial: Partial,
+    keep: usize,
+    link: impl FnOnce(Option<Rc<Pick>>) -> Option<Rc<Pick>>,
+) -> bool {
+    let at = into.partition_point(|kept| kept.value >= partial.value);
+    if at >= keep {
+        return false;
+    }
+    partial.picks = link(partial.picks);
+    // Duplicates are worth the same, so sit right before `at`.
+    let players = chosen(&partial.picks);
+    if into[..at]
+        .iter()
+        .rev()
+        .take_while(|kept| kept.value == partial.value)
+        .any(|kept| chosen(&kept.picks) == players)
+    {
+        return true;
+    }
+    into.insert(at, partial);
+    into.truncate(keep);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::players_frame;
+
+    fn frame(players: &[(&str, &str, f64)]) -> DataFrame {
+        let players: Vec<Player> = players
+            .iter()
+            .map(|&(name, position, salary)| Player {
+                first_name: name.to_string(),
+                last_name: String::new(),
+                team: "Team".to_string(),
+                position: position.to_string(),
+                salary,
+            })
+            .collect();
+        players_frame(&players).unwrap()
+    }
+
+    // Values are scores rather than salaries so the optimum is unique.
+    fn squad() -> DataFrame {
+        frame(&[
+            ("G1", "GK", 100.0),
+            ("G2", "GK", 50.0),
+            ("D1", "D", 300.0),
+            ("D2", "D", 200.0),
+            ("D3", "D", 100.0),
+            ("H1", "D-M", 250.0),
+            ("M2", "M", 150.0),
+        ])
+    }
+
+    fn request(budget: f64) -> RosterRequest {
+        let values = [
+            ("G1", 5.0),
+            ("G2", 4.0),
+            ("D1", 9.0),
+            ("D2", 7.0),
+            ("D3", 3.0),
+            ("H1", 8.0),
+            ("M2", 6.0),
+        ];
+        RosterRequest {
+            budget,
+            quotas: [("GK", 1), ("D", 2), ("M", 1)]
+                .into_iter()
+                .map(|(position, count)| (position.to_string(), count))
+                .collect(),
+            values: Some(
+                values
+                    .into_iter()
+                    .map(|(name, value)| (format!("{} ", name), value))
+                    .collect(),
+            ),
+            alternatives: 2,
+        }
+    }
+
+    // "name:slot" per pick, sorted by name.
+    fn picks(roster: &Roster) -> Vec<String> {
+        let mut picks: Vec<String> = roster
+            .players
+            .iter()
+            .map(|pick| format!("{}:{}", pick.player.first_name, pick.slot))
+            .collect();
+        picks.sort();
+        picks
+    }
+
+    #[test]
+    fn finds_the_best_roster_and_runners_up() {
+        // Checked by enumerating every 4-player combination.
+        let result = build_roster(&squad(), &request(1000.0)).unwrap();
+        let best = result.best.unwrap();
+        assert_eq!(picks(&best), ["D1:D", "D2:D", "G1:GK", "H1:M"]);
+        assert_eq!((best.total_value, best.total_salary), (29.0, 850.0));
+
+        let mut runners_up: Vec<_> = result.alternatives.iter().map(picks).collect();
+        runners_up.sort();
+        assert_eq!(
+            runners_up,
+            [
+                ["D1:D", "D2:D", "G2:GK", "H1:M"],
+                ["D1:D", "G1:GK", "H1:D", "M2:M"],
+            ]
+        );
+        assert!(result
+            .alternatives
+            .iter()
+            .all(|roster| roster.total_value == 28.0));
+    }
+
+    #[test]
+    fn fills_every_quota_exactly() {
+        let result = build_roster(&squad(), &request(1000.0)).unwrap();
+        for roster in result.best.iter().chain(&result.alternatives) {
+            let mut filled = BTreeMap::new();
+            for pick in &roster.players {
+                assert!(pick
+                    .player
+                    .position
+                    .split('-')
+                    .any(|part| part == pick.slot));
+                *filled.entry(pick.slot.clone()).or_insert(0) += 1;
+            }
+            assert_eq!(filled, request(0.0).quotas);
+        }
+    }
+
+    #[test]
+    fn stays_within_the_budget() {
+        // The best roster costs exactly 850.
+        let at = build_roster(&squad(), &request(850.0)).unwrap();
+        assert_eq!(at.best.unwrap().total_value, 29.0);
+        let below = build_roster(&squad(), &request(849.99)).unwrap();
+        let best = below.best.unwrap();
+        assert_eq!(best.total_value, 28.0);
+        assert!(best.total_salary <= 849.99);
+
+        // The cheapest roster costs 500.
+        let cheapest = build_roster(&squad(), &request(500.0)).unwrap();
+        assert_eq!(cheapest.best.unwrap().total_salary, 500.0);
+        assert!(cheapest.alternatives.is_empty());
+    }
+
+    #[test]
+    fn reports_infeasible_requests_as_no_roster() {
+        let poor = build_roster(&squad(), &request(499.0)).unwrap();
+        assert_eq!(poor.best, None);
+        assert!(poor.alternatives.is_empty());
+
+        let mut forwards = request(1000.0);
+        forwards.quotas.insert("F".to_string(), 1);
+        assert_eq!(build_roster(&squad(), &forwards).unwrap().best, None);
+    }
+
+    #[test]
+    fn hybrids_in_swapped_slots_are_one_roster() {
+        let df = frame(&[
+            ("H1", "D-M", 100.0),
+            ("H2", "D-M", 90.0),
+            ("D3", "D", 80.0),
+            ("M3", "M", 70.0),
+        ]);
+        let request = RosterRequest {
+            budget: 1000.0,
+            quotas: [("D".to_string(), 1), ("M".to_string(), 1)].into(),
+            values: None,
+            alternatives: 2,
+        };
+        let result = build_roster(&df, &request).unwrap();
+        assert_eq!(result.best.unwrap().total_value, 190.0);
+        let values: Vec<f64> = result
+            .alternatives
+            .iter()
+            .map(|roster| roster.total_value)
+            .collect();
+        assert_eq!(values, [180.0, 170.0]);
+    }
+
+    #[test]
+    fn rejects_requests_beyond_the_limits() {
+        let mut huge = request(1000.0);
+        huge.quotas.insert("D".to_string(), 1_000_000);
+        let mut overflow = request(1000.0);
+        overflow.quotas.insert("F".to_string(), usize::MAX);
+        let mut alternatives = request(1000.0);
+        alternatives.alternatives = MAX_ALTERNATIVES + 1;
+        // Within the squad size, but 6^6 * 1001 table entries.
+        let mut spread = request(1_000_000.0);
+        spread.quotas = ["GK", "D", "M", "F", "W", "S"]
+            .into_iter()
+            .map(|position| (position.to_string(), 5))
+            .collect();
+
+        for bad in [
+            huge,
+            overflow,
+            alternatives,
+            spread,
+            request(f64::NAN),
+            request(f64::INFINITY),
+            request(-1.0),
+        ] {
+            assert!(build_roster(&squad(), &bad).is_err(), "{:?}", bad);
+        }
+    }
+}
@@ -0,0 +1,336 @@
+// MLS roster-rule compliance.
+//
+// Every player is classified against a configurable `RuleSet` and each team
+// gets its salary budget usage, headroom and any rule violations:
+//  - designated players (DPs) are the players paid above `dp_threshold`;
+//    each one charges `dp_budget_charge` against the budget
+//  - the remaining highest earners fill the senior roster slots (DPs take
+//    senior slots too) and charge their salary up to `max_budget_charge`;
+//    anything above that has to be bought down with targeted allocation
+//    money (TAM)
+//  - everyone else, and anyone paid below the senior minimum, sits on the
+//    supplemental roster and does not count against the budget
+//
+// General allocation money (GAM) buy-downs are not modelled, so the budget
+// charge is an upper bound on what a team actually reports.
+
+use crate::bands::{MAX_BUDGET_CHARGE, SENIOR_MINIMUM, TAM_MAXIMUM};
+use crate::{players, Player};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(default)]
+pub struct RuleSet {
+    pub salary_budget: f64,
+    pub max_budget_charge: f64,
+    pub dp_threshold: f64,
+    pub dp_budget_charge: f64,
+    pub max_dps: usize,
+    pub senior_minimum: f64,
+    pub senior_roster_slots: usize,
+    pub supplemental_roster_slots: usize,
+}
+
+// MLS 2023 roster rules.
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet {
+            salary_budget: 5_210_000.0,
+            max_budget_charge: MAX_BUDGET_CHARGE,
+            dp_threshold: TAM_MAXIMUM,
+            dp_budget_charge: MAX_BUDGET_CHARGE,
+            max_dps: 3,
+            senior_minimum: SENIOR_MINIMUM,
+            senior_roster_slots: 20,
+            supplemental_roster_slots: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Category {
+    DesignatedPlayer,
+    Senior,
+    Supplemental,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ClassifiedPlayer {
+    #[serde(flatten)]
+    pub player: Player,
+    pub category: Category,
+    pub budget_charge: f64,
+    // Salary above the budget charge that must be bought down with TAM.
+    pub tam_required: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct TeamCompliance {
+    pub team: String,
+    pub compliant: bool,
+    pub violations: Vec<String>,
+    pub designated_players: usize,
+    pub senior_players: usize,
+    pub supplemental_players: usize,
+    pub budget_charge: f64,
+    pub headroom: f64,
+    pub tam_required: f64,
+    pub players: Vec<ClassifiedPlayer>,
+}
+
+impl RuleSet {
+    // Classifies one team's players and checks them against the rules.
+    pub fn check_team(&self, team: String, mut roster: Vec<Player>) -> TeamCompliance {
+        roster.sort_by(|a, b| b.salary.total_cmp(&a.salary));
+
+        let mut senior_slots_used = 0;
+        let players: Vec<ClassifiedPlayer> = roster
+            .into_iter()
+            .map(|player| {
+                let category = if player.salary > self.dp_threshold {
+                    Category::DesignatedPlayer
+                } else if player.salary >= self.senior_minimum
+                    && senior_slots_used < self.senior_roster_slots
+                {
+                    Category::Senior
+                } else {
+                    Category::Supplemental
+                };
+                if category != Category::Supplemental {
+                    senior_slots_used += 1;
+                }
+
+                let budget_charge = match category {
+                    Category::DesignatedPlayer => self.dp_budget_charge,
+                    Category::Senior => player.salary.min(self.max_budget_charge),
+                    Category::Supplemental => 0.0,
+                };
+                let tam_required = match category {
+                    Category::Senior => player.salary - budget_charge,
+                    Category::DesignatedPlayer | Category::Supplemental => 0.0,
+                };
+                ClassifiedPlayer {
+                    player,
+                    category,
+                    budget_charge,
+                    tam_required,
+                }
+            })
+            .collect();
+
+        let count = |category| players.iter().filter(|p| p.category == category).count();
+        let designated_players = count(Category::DesignatedPlayer);
+        let senior_players = count(Category::Senior);
+        let supplemental_players = count(Category::Supplemental);
+        let budget_charge: f64 = players.iter().map(|p| p.budget_charge).sum();
+        let tam_required: f64 = players.iter().map(|p| p.tam_required).sum();
+        let headroom = self.salary_budget - budget_charge;
+
+        let mut violations = Vec::new();
+        if designated_players > self.max_dps {
+            violations.push(format!(
+                "{} designated players, at most {} allowed",
+                designated_players, self.max_dps
+            ));
+        }
+        if designated_players + senior_players > self.senior_roster_slots {
+            violations.push(format!(
+                "{} players on the senior roster, {} slots available",
+                designated_players + senior_players,
+                self.senior_roster_slots
+            ));
+        }
+        if supplemental_players > self.supplemental_roster_slots {
+            violations.push(format!(
+                "{} players on the supplemental roster, {} slots available",
+                supplemental_players, self.supplemental_roster_slots
+            ));
+        }
+        if headroom < 0.0 {
+            violations.push(format!(
+                "budget charge {:.2} exceeds the salary budget {:.2}",
+                budget_charge, self.salary_budget
+            ));
+        }
+
+        TeamCompliance {
+            team,
+            compliant: violations.is_empty(),
+            violations,
+            designated_players,
+            senior_players,
+            supplemental_players,
+            budget_charge,
+            headroom,
+            tam_required,
+            players,
+        }
+    }
+}
+
+// Checks every team in the salaries data, ordered by team name.
+pub fn compliance(df: &DataFrame, rules: &RuleSet) -> Result<Vec<TeamCompliance>, PolarsError> {
+    let mut rosters: BTreeMap<String, Vec<Player>> = BTreeMap::new();
+    for player in players(df)? {
+        rosters.entry(player.team.clone()).or_default().push(player);
+    }
+    Ok(rosters
+        .into_iter()
+        .map(|(team, roster)| rules.check_team(team, roster))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::players_frame;
+
+    // Round numbers: DPs above 500 charge 300, three senior slots, two
+    // supplemental ones.
+    fn rules() -> RuleSet {
+        RuleSet {
+            salary_budget: 1_000.0,
+            max_budget_charge: 300.0,
+            dp_threshold: 500.0,
+            dp_budget_charge: 300.0,
+            max_dps: 1,
+            senior_minimum: 50.0,
+            senior_roster_slots: 3,
+            supplemental_roster_slots: 2,
+        }
+    }
+
+    fn roster(team: &str, salaries: &[f64]) -> Vec<Player> {
+        salaries
+            .iter()
+            .enumerate()
+            .map(|(i, &salary)| Player {
+                first_name: format!("P{}", i),
+                last_name: team.to_string(),
+                team: team.to_string(),
+                position: "M".to_string(),
+                salary,
+            })
+            .collect()
+    }
+
+    fn check(rules: &RuleSet, salaries: &[f64]) -> TeamCompliance {
+        rules.check_team("T".to_string(), roster("T", salaries))
+    }
+
+    #[test]
+    fn classifies_and_charges_each_player() {
+        // Given out of order on purpose: players are classified by salary.
+        let team = check(&rules(), &[200.0, 40.0, 800.0, 100.0, 400.0]);
+        let classified: Vec<_> = team
+            .players
+            .iter()
+            .map(|p| (p.player.salary, p.category, p.budget_charge, p.tam_required))
+            .collect();
+        assert_eq!(
+            classified,
+            vec![
+                // A DP charges the flat DP charge, whatever the salary.
+                (800.0, Category::DesignatedPlayer, 300.0, 0.0),
+                // Seniors charge up to the maximum, the rest needs TAM.
+                (400.0, Category::Senior, 300.0, 100.0),
+                (200.0, Category::Senior, 200.0, 0.0),
+                // The DP took a senior slot, so the senior roster is full.
+                (100.0, Category::Supplemental, 0.0, 0.0),
+                // Below the senior minimum.
+                (40.0, Category::Supplemental, 0.0, 0.0),
+            ]
+        );
+        assert_eq!(
+            (
+                team.designated_players,
+                team.senior_players,
+                team.supplemental_players
+            ),
+            (1, 2, 2)
+        );
+        assert_eq!(team.budget_charge, 800.0);
+        assert_eq!(team.headroom, 200.0);
+        assert_eq!(team.tam_required, 100.0);
+        assert!(team.compliant);
+        assert!(team.violations.is_empty());
+    }
+
+    #[test]
+    fn too_many_designated_players() {
+        let team = check(&rules(), &[900.0, 800.0]);
+        assert!(!team.compliant);
+        assert_eq!(
+            team.violations,
+            vec!["2 designated players, at most 1 allowed"]
+        );
+    }
+
+    #[test]
+    fn designated_players_overfill_the_senior_roster() {
+        // DPs always take a senior slot, even when none are left.
+        let rules = RuleSet {
+            max_dps: 10,
+            salary_budget: 10_000.0,
+            ..rules()
+        };
+        let team = check(&rules, &[900.0, 800.0, 700.0, 600.0]);
+        assert_eq!(
+            team.violations,
+            vec!["4 players on the senior roster, 3 slots available"]
+        );
+    }
+
+    #[test]
+    fn too_many_supplemental_players() {
+        let team = check(&rules(), &[40.0, 30.0, 20.0]);
+        assert_eq!(team.budget_charge, 0.0);
+        assert_eq!(
+            team.violations,
+            vec!["3 players on the supplemental roster, 2 slots available"]
+        );
+    }
+
+    #[test]
+    fn over_the_salary_budget() {
+        let rules = RuleSet {
+            salary_budget: 500.0,
+            ..rules()
+        };
+        let team = check(&rules, &[400.0, 300.0]);
+        assert_eq!(team.headroom, -100.0);
+        assert_eq!(
+            team.violations,
+            vec!["budget charge 600.00 exceeds the salary budget 500.00"]
+        );
+    }
+
+    #[test]
+    fn every_violation_at_once() {
+        let rules = RuleSet {
+            salary_budget: 500.0,
+            senior_roster_slots: 1,
+            supplemental_roster_slots: 0,
+            ..rules()
+        };
+        let team = check(&rules, &[900.0, 800.0, 400.0, 40.0]);
+        assert_eq!(team.violations.len(), 4, "{:?}", team.violations);
+    }
+
+    #[test]
+    fn checks_every_team_in_name_order() {
+        let mut players = roster("B", &[900.0, 800.0]);
+        players.extend(roster("A", &[200.0]));
+        let teams = compliance(&players_frame(&players).unwrap(), &rules()).unwrap();
+        let summary: Vec<_> = teams
+            .iter()
+            .map(|t| (t.team.as_str(), t.players.len(), t.compliant))
+            .collect();
+        assert_eq!(summary, vec![("A", 1, true), ("B", 2, false)]);
+    }
+}
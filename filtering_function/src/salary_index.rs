@@ -0,0 +1,161 @@
+// Per-team sorted salaries for threshold queries.
+//
+// `League` builds the index once when it loads a league, so "players above X
+// per team" is one binary search per team instead of a filter and group-by
+// over the whole table. Answers match `count_above` exactly: rows without a
+// salary, and NaN salaries, never pass its filter so are left out; rows
+// without a team are counted under a null team, as its group-by does; and
+// a NaN threshold matches nobody. The tests below check the two agree;
+// benches/salary_index.rs compares their speed. Arbitrary filters still go
+// through the lazy Polars path (see filter.rs).
+
+use polars::prelude::*;
+use std::collections::BTreeMap;
+
+struct TeamSalaries {
+    team: Option<String>,
+    // Ascending.
+    salaries: Vec<f64>,
+}
+
+impl TeamSalaries {
+    fn above(&self, threshold: f64) -> usize {
+        // Every comparison with NaN is false.
+        if threshold.is_nan() {
+            return 0;
+        }
+        self.salaries.len() - self.salaries.partition_point(|&salary| salary <= threshold)
+    }
+}
+
+pub struct SalaryIndex {
+    // By team name, the null team first.
+    teams: Vec<TeamSalaries>,
+}
+
+impl SalaryIndex {
+    pub fn build(df: &DataFrame) -> Result<Self, PolarsError> {
+        let teams = df.column("team")?.utf8()?;
+        let salaries = df.column("salary")?.cast(&DataType::Float64)?;
+        let mut by_team: BTreeMap<Option<&str>, Vec<f64>> = BTreeMap::new();
+        for (team, salary) in teams.into_iter().zip(salaries.f64()?) {
+            if let Some(salary) = salary.filter(|salary| !salary.is_nan()) {
+                by_team.entry(team).or_default().push(salary);
+            }
+        }
+        let teams = by_team
+            .into_iter()
+            .map(|(team, mut salaries)| {
+                salaries.sort_by(f64::total_cmp);
+                TeamSalaries {
+                    team: team.map(str::to_string),
+                    salaries,
+                }
+            })
+            .collect();
+        Ok(SalaryIndex { teams })
+    }
+
+    // Players earning more than `threshold` per team, for teams with any.
+    pub fn count_above(&self, threshold: f64) -> Vec<(Option<&str>, usize)> {
+        self.teams
+            .iter()
+            .map(|team| (team.team.as_deref(), team.above(threshold)))
+            .filter(|(_, players)| *players > 0)
+            .collect()
+    }
+
+    // `count_above` as the frame `crate::count_above` returns: `team` and
+    // the count in `position`, ordered by team.
+    pub fn count_above_frame(&self, threshold: f64) -> Result<DataFrame, PolarsError> {
+        let (teams, players): (Vec<Option<&str>>, Vec<u32>) = self
+            .count_above(threshold)
+            .into_iter()
+            .map(|(team, players)| (team, players as u32))
+            .unzip();
+        DataFrame::new(vec![
+            Series::new("team", teams),
+            Series::new("position", players),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{count_above, load_salaries};
+
+    // `count_above` ordered as `count_above_frame` orders its rows.
+    fn lazy(df: &DataFrame, threshold: f64) -> DataFrame {
+        count_above(df, threshold)
+            .unwrap()
+            .sort(["team"], false, false)
+            .unwrap()
+    }
+
+    fn assert_agree(df: &DataFrame, thresholds: &[f64]) {
+        let index = SalaryIndex::build(df).unwrap();
+        for &threshold in thresholds {
+            let expected = lazy(df, threshold);
+            let actual = index.count_above_frame(threshold).unwrap();
+            assert!(
+                actual.frame_equal_missing(&expected),
+                "at {}: index\n{}\nlazy\n{}",
+                threshold,
+                actual,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn agrees_with_count_above_on_the_embedded_league() {
+        let df = load_salaries().unwrap();
+        let salaries = df.column("salary").unwrap().f64().unwrap();
+        let mut thresholds = vec![0.0, 100_000.0, 800_000.0, 5_000_000.0];
+        // Ties: thresholds equal to salaries in the data.
+        thresholds.extend(salaries.into_no_null_iter().step_by(97));
+        assert_agree(&df, &thresholds);
+    }
+
+    #[test]
+    fn agrees_with_count_above_on_edge_cases() {
+        let df = df!(
+            "team" => [Some("A"), Some("A"), Some("A"), Some("B"), None, None, Some("C")],
+            "position" => ["D", "M", "F", "D", "M", "F", "GK"],
+            "salary" => [Some(100.0), Some(200.0), Some(200.0), Some(f64::NAN), Some(300.0), None, None],
+        )
+        .unwrap();
+        assert_agree(
+            &df,
+            &[
+                f64::NAN,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::MIN,
+                99.0,
+                100.0,
+                150.0,
+                200.0,
+                300.0,
+            ],
+        );
+    }
+
+    #[test]
+    fn counts_strictly_above() {
+        let df = df!(
+            "team" => [Some("A"), Some("A"), None],
+            "salary" => [100.0, 200.0, 300.0],
+        )
+        .unwrap();
+        let index = SalaryIndex::build(&df).unwrap();
+        assert_eq!(index.count_above(100.0), [(None, 1), (Some("A"), 1)]);
+        assert_eq!(
+            index.count_above(f64::NEG_INFINITY),
+            [(None, 1), (Some("A"), 2)]
+        );
+        assert!(index.count_above(f64::NAN).is_empty());
+        assert!(index.count_above(f64::INFINITY).is_empty());
+    }
+}
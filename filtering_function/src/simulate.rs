@@ -0,0 +1,395 @@
+// What-if roster simulation.
+//
+// A list of edits (transfers, salary changes, signings and releases) is
+// applied to a copy of the salaries DataFrame and one of the supported
+// aggregations is computed on the original and on the edited copy, so the
+// caller sees the before/after effect of the moves. The served dataset itself
+// is never modified.
+
+use crate::bands::{band_matrix, BandSchema};
+use crate::inequality::team_inequality;
+use crate::rules::{compliance, RuleSet};
+use crate::top_n::{top_n, Grouping, Ties};
+use crate::{count_above, players, players_frame, Player};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use utoipa::openapi::schema::{
+    AllOfBuilder, Discriminator, ObjectBuilder, OneOfBuilder, Ref, Schema, SchemaType,
+};
+use utoipa::openapi::RefOr;
+use utoipa::ToSchema;
+
+// Identifies an existing player. `team` is only needed when the name alone
+// matches more than one player.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct PlayerRef {
+    pub first_name: String,
+    pub last_name: String,
+    pub team: Option<String>,
+}
+
+impl PlayerRef {
+    fn matches(&self, player: &Player) -> bool {
+        player.first_name == self.first_name
+            && player.last_name == self.last_name
+            && self.team.as_ref().is_none_or(|team| *team == player.team)
+    }
+
+    // Index of the single player this reference points at.
+    fn find(&self, roster: &[Player]) -> Result<usize, PolarsError> {
+        let mut found = roster
+            .iter()
+            .enumerate()
+            .filter(|(_, player)| self.matches(player))
+            .map(|(i, _)| i);
+        match (found.next(), found.next()) {
+            (Some(i), None) => Ok(i),
+            (None, _) => Err(PolarsError::ComputeError(
+                format!("no player named {} {}", self.first_name, self.last_name).into(),
+            )),
+            (Some(_), Some(_)) => Err(PolarsError::ComputeError(
+                format!(
+                    "several players named {} {}, pass a team to pick one",
+                    self.first_name, self.last_name
+                )
+                .into(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Edit {
+    Transfer {
+        #[serde(flatten)]
+        player: PlayerRef,
+        to_team: String,
+    },
+    SetSalary {
+        #[serde(flatten)]
+        player: PlayerRef,
+        salary: f64,
+    },
+    Add {
+        #[serde(flatten)]
+        player: Player,
+    },
+    Release {
+        #[serde(flatten)]
+        player: PlayerRef,
+    },
+}
+
+// Written by hand: the derive cannot combine the `action` tag with the
+// flattened player fields.
+impl<'s> ToSchema<'s> for Edit {
+    fn schema() -> (&'s str, RefOr<Schema>) {
+        let variant = |action: &str, player: &str, field: Option<(&str, SchemaType)>| {
+            let mut own = ObjectBuilder::new()
+                .property(
+                    "action",
+                    ObjectBuilder::new()
+                        .schema_type(SchemaType::String)
+                        .enum_values(Some([action])),
+                )
+                .required("action");
+            if let Some((name, schema_type)) = field {
+                own = own
+                    .property(name, ObjectBuilder::new().schema_type(schema_type))
+                    .required(name);
+            }
+            AllOfBuilder::new()
+                .item(Ref::from_schema_name(player))
+                .item(own)
+        };
+        let schema = OneOfBuilder::new()
+            .item(variant(
+                "transfer",
+                "PlayerRef",
+                Some(("to_team", SchemaType::String)),
+            ))
+            .item(variant(
+                "set_salary",
+                "PlayerRef",
+                Some(("salary", SchemaType::Number)),
+            ))
+            .item(variant("add", "Player", None))
+            .item(variant("release", "PlayerRef", None))
+            .discriminator(Some(Discriminator::new("action")));
+        ("Edit", schema.into())
+    }
+}
+
+impl Edit {
+    fn apply(&self, roster: &mut Vec<Player>) -> Result<(), PolarsError> {
+        match self {
+            Edit::Transfer { player, to_team } => {
+                let i = player.find(roster)?;
+                roster[i].team = to_team.clone();
+            }
+            Edit::SetSalary { player, salary } => {
+                let i = player.find(roster)?;
+                roster[i].salary = *salary;
+            }
+            Edit::Add { player } => roster.push(player.clone()),
+            Edit::Release { player } => {
+                let i = player.find(roster)?;
+                roster.remove(i);
+            }
+        }
+        Ok(())
+    }
+}
+
+// The aggregations a simulation can report on.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Aggregation {
+    // Players above `threshold` per team, like `calculate`.
+    CountAbove {
+        threshold: f64,
+    },
+    TopN {
+        n: u32,
+        grouping: Grouping,
+        #[serde(default)]
+        ties: Ties,
+    },
+    Inequality,
+    Bands {
+        schema: Option<BandSchema>,
+    },
+    Compliance {
+        #[serde(default)]
+        rules: RuleSet,
+    },
+}
+
+impl Aggregation {
+    pub fn evaluate(&self, df: &DataFrame) -> Result<Value, PolarsError> {
+        let value = match self {
+            Aggregation::CountAbove { threshold } => {
+                let counts = count_above(df, *threshold)?;
+                let teams = counts.column("team")?.utf8()?;
+                let players = counts.column("position")?.u32()?;
+                let by_team: BTreeMap<&str, u32> = teams
+                    .into_iter()
+                    .zip(players)
+                    .filter_map(|(team, count)| Some((team?, count?)))
+                    .collect();
+                serde_json::to_value(by_team)
+            }
+            Aggregation::TopN { n, grouping, ties } => {
+                serde_json::to_value(players(&top_n(df, *n, *grouping, *ties)?)?)
+            }
+            Aggregation::Inequality => serde_json::to_value(team_inequality(df)?),
+            Aggregation::Bands { schema } => {
+                let schema = schema.clone().unwrap_or_else(BandSchema::standard);
+                serde_json::to_value(band_matrix(df, &schema)?)
+            }
+            Aggregation::Compliance { rules } => serde_json::to_value(compliance(df, rules)?),
+        };
+        value.map_err(|err| PolarsError::ComputeError(err.to_string().into()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct Simulation {
+    pub edits: Vec<Edit>,
+    pub aggregation: Aggregation,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct SimulationResult {
+    pub before: Value,
+    pub after: Value,
+}
+
+// Applies the edits in order to a copy of `df`. Fails on the first edit that
+// references a missing or ambiguous player.
+pub fn apply_edits(df: &DataFrame, edits: &[Edit]) -> Result<DataFrame, PolarsError> {
+    let mut roster = players(df)?;
+    for edit in edits {
+        edit.apply(&mut roster)?;
+    }
+    players_frame(&roster)
+}
+
+pub fn simulate(df: &DataFrame, simulation: &Simulation) -> Result<SimulationResult, PolarsError> {
+    let edited = apply_edits(df, &simulation.edits)?;
+    Ok(SimulationResult {
+        before: simulation.aggregation.evaluate(df)?,
+        after: simulation.aggregation.evaluate(&edited)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn player(first_name: &str, last_name: &str, team: &str, salary: f64) -> Player {
+        Player {
+            first_name: first_name.to_string(),
+            last_name: last_name.to_string(),
+            team: team.to_string(),
+            position: "M".to_string(),
+            salary,
+        }
+    }
+
+    // Two players share a name on different teams.
+    fn frame() -> DataFrame {
+        players_frame(&[
+            player("Ana", "Silva", "A", 100.0),
+            player("Ben", "Jones", "A", 50.0),
+            player("Ana", "Silva", "B", 80.0),
+            player("Cal", "Reed", "B", 20.0),
+        ])
+        .unwrap()
+    }
+
+    fn edits(edits: Value) -> Vec<Edit> {
+        serde_json::from_value(edits).unwrap()
+    }
+
+    fn roster(json: Value) -> Vec<Player> {
+        players(&apply_edits(&frame(), &edits(json)).unwrap()).unwrap()
+    }
+
+    fn error(json: Value) -> String {
+        apply_edits(&frame(), &edits(json)).unwrap_err().to_string()
+    }
+
+    // Payroll per team from an inequality result.
+    fn payrolls(value: &Value) -> Vec<(String, f64)> {
+        value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|team| {
+                (
+                    team["team"].as_str().unwrap().to_string(),
+                    team["payroll"].as_f64().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn transfer_moves_a_player() {
+        let roster = roster(json!([
+            {"action": "transfer", "first_name": "Ben", "last_name": "Jones", "to_team": "B"}
+        ]));
+        assert_eq!(roster[1], player("Ben", "Jones", "B", 50.0));
+        assert_eq!(roster.len(), 4);
+    }
+
+    #[test]
+    fn set_salary_changes_one_salary() {
+        let roster = roster(json!([
+            {"action": "set_salary", "first_name": "Ana", "last_name": "Silva", "team": "B", "salary": 90.0}
+        ]));
+        assert_eq!(roster[0].salary, 100.0);
+        assert_eq!(roster[2].salary, 90.0);
+    }
+
+    #[test]
+    fn add_signs_a_new_player() {
+        let roster = roster(json!([
+            {"action": "add", "first_name": "Dee", "last_name": "Park", "team": "A", "position": "F", "salary": 30.0}
+        ]));
+        assert_eq!(roster.len(), 5);
+        assert_eq!(roster[4].first_name, "Dee");
+        assert_eq!(roster[4].position, "F");
+    }
+
+    #[test]
+    fn release_removes_a_player() {
+        let roster = roster(json!([
+            {"action": "release", "first_name": "Ana", "last_name": "Silva", "team": "A"}
+        ]));
+        assert_eq!(roster.len(), 3);
+        assert!(!roster.contains(&player("Ana", "Silva", "A", 100.0)));
+        assert!(roster.contains(&player("Ana", "Silva", "B", 80.0)));
+    }
+
+    #[test]
+    fn edits_apply_in_order() {
+        // The transfer makes the released player unambiguous.
+        let roster = roster(json!([
+            {"action": "transfer", "first_name": "Cal", "last_name": "Reed", "to_team": "A"},
+            {"action": "release", "first_name": "Cal", "last_name": "Reed", "team": "A"}
+        ]));
+        assert_eq!(roster.len(), 3);
+    }
+
+    #[test]
+    fn ambiguous_players_need_a_team() {
+        for action in ["transfer", "set_salary", "release"] {
+            let edit = json!({
+                "action": action, "first_name": "Ana", "last_name": "Silva",
+                "to_team": "C", "salary": 1.0
+            });
+            assert!(
+                error(json!([edit])).contains("several players named Ana Silva, pass a team"),
+                "{}",
+                action
+            );
+        }
+    }
+
+    #[test]
+    fn missing_players_are_reported() {
+        assert!(error(json!([
+            {"action": "release", "first_name": "Zed", "last_name": "Nobody"}
+        ]))
+        .contains("no player named Zed Nobody"));
+        // A team that does not match counts as missing.
+        assert!(error(json!([
+            {"action": "release", "first_name": "Ben", "last_name": "Jones", "team": "B"}
+        ]))
+        .contains("no player named Ben Jones"));
+    }
+
+    #[test]
+    fn reports_payrolls_before_and_after() {
+        let simulation = Simulation {
+            edits: edits(json!([
+                {"action": "transfer", "first_name": "Ben", "last_name": "Jones", "to_team": "B"},
+                {"action": "set_salary", "first_name": "Cal", "last_name": "Reed", "salary": 45.0},
+                {"action": "add", "first_name": "Dee", "last_name": "Park", "team": "C", "position": "F", "salary": 30.0}
+            ])),
+            aggregation: Aggregation::Inequality,
+        };
+        let result = simulate(&frame(), &simulation).unwrap();
+        let to = |pairs: &[(&str, f64)]| -> Vec<(String, f64)> {
+            pairs
+                .iter()
+                .map(|&(team, payroll)| (team.to_string(), payroll))
+                .collect()
+        };
+        assert_eq!(payrolls(&result.before), to(&[("A", 150.0), ("B", 100.0)]));
+        assert_eq!(
+            payrolls(&result.after),
+            to(&[("A", 100.0), ("B", 175.0), ("C", 30.0)])
+        );
+    }
+
+    #[test]
+    fn failed_edits_leave_nothing_half_applied() {
+        let simulation = Simulation {
+            edits: edits(json!([
+                {"action": "release", "first_name": "Ben", "last_name": "Jones"},
+                {"action": "release", "first_name": "Ben", "last_name": "Jones"}
+            ])),
+            aggregation: Aggregation::CountAbove { threshold: 0.0 },
+        };
+        let df = frame();
+        assert!(simulate(&df, &simulation).is_err());
+        assert_eq!(df.height(), 4);
+    }
+}
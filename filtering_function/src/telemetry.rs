@@ -0,0 +1,121 @@
+// Request-scoped structured logs.
+//
+// Logs are JSON lines for CloudWatch Logs Insights. `trace` opens a span per
+// request carrying the Lambda request id, method, route and query; handlers
+// add the dataset version and the time spent in each `Phase`, and the
+// closing `request` event records the status and total latency:
+//
+//   {"level":"INFO","message":"request","status":200,"latency_ms":4.1,
+//    "span":{"request_id":"…","route":"/salaries/filter/:value",
+//            "dataset_version":"…","load_ms":0.2,"aggregate_ms":2.9,…}}
+//
+// so `stats avg(span.aggregate_ms) by span.route` works as is.
+
+use axum::{extract::MatchedPath, http::Request, middleware::Next, response::Response};
+use lambda_http::RequestExt;
+use std::time::Instant;
+use tracing::{field::Empty, Instrument, Span};
+
+pub fn init() {
+    tracing_subscriber::fmt()
+        .json()
+        .with_max_level(tracing::Level::INFO)
+        // disable printing the name of the module in every log line.
+        .with_target(false)
+        // disabling time is handy because CloudWatch will add the ingestion time.
+        .without_time()
+        // event fields at the top level, the request span's under `span`.
+        .flatten_event(true)
+        .with_current_span(true)
+        .with_span_list(false)
+        .init();
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Phase {
+    // Reading the league's salaries.
+    Load,
+    // Selecting rows.
+    Filter,
+    // Grouping and computing statistics.
+    Aggregate,
+    // Rendering the response body.
+    Serialize,
+}
+
+impl Phase {
+    fn field(self) -> &'static str {
+        match self {
+            Phase::Load => "load_ms",
+            Phase::Filter => "filter_ms",
+            Phase::Aggregate => "aggregate_ms",
+            Phase::Serialize => "serialize_ms",
+        }
+    }
+}
+
+fn millis(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+// Runs `f` and records its duration on the request span.
+pub fn phase<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let value = f();
+    Span::current().record(phase.field(), millis(start));
+    value
+}
+
+// `phase` for a future, e.g. a GraphQL query resolving its fields.
+pub async fn phase_async<T>(phase: Phase, f: impl std::future::Future<Output = T>) -> T {
+    let start = Instant::now();
+    let value = f.await;
+    Span::current().record(phase.field(), millis(start));
+    value
+}
+
+// Records the version of the data the request is answered from.
+pub fn dataset_version(version: &str) {
+    Span::current().record("dataset_version", version);
+}
+
+// Runs the request inside its span and logs its outcome.
+pub async fn trace<B>(request: Request<B>, next: Next<B>) -> Response {
+    let request_id = request
+        .lambda_context_ref()
+        .map(|context| context.request_id.clone())
+        .or_else(|| {
+            request
+                .headers()
+                .get("x-request-id")
+                .and_then(|id| id.to_str().ok())
+                .map(str::to_string)
+        })
+        .unwrap_or_default();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or(request.uri().path(), MatchedPath::as_str);
+    let span = tracing::info_span!(
+        "request",
+        request_id,
+        method = %request.method(),
+        route,
+        query = request.uri().query().unwrap_or_default(),
+        dataset_version = Empty,
+        load_ms = Empty,
+        filter_ms = Empty,
+        aggregate_ms = Empty,
+        serialize_ms = Empty,
+    );
+    let start = Instant::now();
+    let response = next.run(request).instrument(span.clone()).await;
+    span.in_scope(|| {
+        tracing::info!(
+            status = response.status().as_u16(),
+            latency_ms = millis(start),
+            "request"
+        )
+    });
+    response
+}
@@ -0,0 +1,84 @@
+// Top-N earners within a grouping (team, position or the whole league).
+//
+// Players are ranked by salary (highest first) with a window function
+// partitioned by the grouping column, then every row whose rank is within
+// `n` is kept. How tied salaries are ranked is controlled by `Ties`.
+
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// The column players are ranked within.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Grouping {
+    Team,
+    Position,
+    League,
+}
+
+impl Grouping {
+    // The partition column, or `None` when ranking league-wide.
+    pub fn column(self) -> Option<&'static str> {
+        match self {
+            Grouping::Team => Some("team"),
+            Grouping::Position => Some("position"),
+            Grouping::League => None,
+        }
+    }
+}
+
+// How players with the same salary are ranked.
+//  - ordinal: ties broken by row order, exactly `n` players per group
+//  - min:     ties share the lowest rank (1, 2, 2, 4), so a tie at the cut-off
+//             can return more than `n` players
+//  - dense:   ties share a rank with no gaps (1, 2, 2, 3), so `n` counts
+//             distinct salaries rather than players
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Ties {
+    Ordinal,
+    #[default]
+    Min,
+    Dense,
+}
+
+impl From<Ties> for RankMethod {
+    fn from(ties: Ties) -> Self {
+        match ties {
+            Ties::Ordinal => RankMethod::Ordinal,
+            Ties::Min => RankMethod::Min,
+            Ties::Dense => RankMethod::Dense,
+        }
+    }
+}
+
+// Returns the top `n` earners per group with a `rank` column, sorted by
+// group and rank.
+pub fn top_n(
+    df: &DataFrame,
+    n: u32,
+    grouping: Grouping,
+    ties: Ties,
+) -> Result<DataFrame, PolarsError> {
+    let rank = col("salary").rank(
+        RankOptions {
+            method: ties.into(),
+            descending: true,
+        },
+        None,
+    );
+
+    let (rank, sort_by) = match grouping.column() {
+        Some(group) => (rank.over([col(group)]), vec![col(group), col("rank")]),
+        None => (rank, vec![col("rank")]),
+    };
+    let descending = vec![false; sort_by.len()];
+
+    df.clone()
+        .lazy()
+        .with_column(rank.alias("rank"))
+        .filter(col("rank").lt_eq(lit(n)))
+        .sort_by_exprs(sort_by, descending, false, true)
+        .collect()
+}
@@ -0,0 +1,141 @@
+// Keeps /openapi.json in step with the router: every path `app` routes must
+// be documented with the methods it allows, and every documented operation
+// must be served.
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, Response, StatusCode};
+use polars_lambda_axum::app::{app, openapi, routes};
+use polars_lambda_axum::currency::RateTable;
+use std::collections::BTreeSet;
+use tower::ServiceExt;
+
+const METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+// (method, path) of every documented operation, with `{param}` segments.
+fn documented() -> BTreeSet<(String, String)> {
+    let spec = serde_json::to_value(openapi()).unwrap();
+    let mut operations = BTreeSet::new();
+    for (path, item) in spec["paths"].as_object().unwrap() {
+        for method in METHODS {
+            if item.get(method).is_some() {
+                operations.insert((method.to_string(), path.clone()));
+            }
+        }
+    }
+    operations
+}
+
+// Axum's `:param` segments in OpenAPI's `{param}` form.
+fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(param) => format!("{{{}}}", param),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// A value for each path parameter that the handlers accept.
+fn example(param: &str) -> &'static str {
+    match param {
+        "league" => "mls",
+        "value" => "1000000",
+        "grouping" => "team",
+        "n" => "3",
+        "players" => "10",
+        "chart" => "bar",
+        "team" => "Toronto%20FC",
+        other => panic!("no example value for path parameter {}", other),
+    }
+}
+
+// `path` with an example value in each `{param}` segment.
+fn uri(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix('{') {
+            Some(param) => example(param.trim_end_matches('}')).to_string(),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+async fn send(method: Method, uri: &str) -> Response<axum::body::BoxBody> {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap();
+    app::<Body>(RateTable::default())
+        .oneshot(request)
+        .await
+        .unwrap()
+}
+
+#[test]
+fn every_route_is_documented() {
+    let routed: BTreeSet<_> = routes().iter().map(|path| openapi_path(path)).collect();
+    let documented: BTreeSet<_> = documented().into_iter().map(|(_, path)| path).collect();
+    let undocumented: Vec<_> = routed.difference(&documented).collect();
+    let unrouted: Vec<_> = documented.difference(&routed).collect();
+    assert!(
+        undocumented.is_empty() && unrouted.is_empty(),
+        "routes missing from the OpenAPI document: {:?}\n\
+         documented paths without a route: {:?}",
+        undocumented,
+        unrouted
+    );
+}
+
+#[tokio::test]
+async fn every_allowed_method_is_documented() {
+    let documented = documented();
+    let paths: BTreeSet<_> = documented.iter().map(|(_, path)| path.clone()).collect();
+    for path in paths {
+        // No route takes TRACE, so the router answers 405 listing the
+        // methods it does take.
+        let response = send(Method::TRACE, &uri(&path)).await;
+        assert_eq!(
+            response.status(),
+            StatusCode::METHOD_NOT_ALLOWED,
+            "{}",
+            path
+        );
+        let allowed: BTreeSet<_> = response.headers()[header::ALLOW]
+            .to_str()
+            .unwrap()
+            .split(',')
+            .map(|method| method.trim().to_lowercase())
+            .filter(|method| method != "head")
+            .collect();
+        let expected: BTreeSet<_> = documented
+            .iter()
+            .filter(|(_, documented)| *documented == path)
+            .map(|(method, _)| method.clone())
+            .collect();
+        assert_eq!(allowed, expected, "{}", path);
+    }
+}
+
+#[tokio::test]
+async fn every_documented_operation_is_served() {
+    for (method, path) in documented() {
+        let uri = uri(&path);
+        let response = send(method.to_uppercase().parse().unwrap(), &uri).await;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        // Unrouted paths get the router's empty 404 or 405; handlers that
+        // reject the request always explain why.
+        assert!(
+            !matches!(
+                status,
+                StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+            ) || !body.is_empty(),
+            "{} {} is documented but not routed ({})",
+            method.to_uppercase(),
+            uri,
+            status
+        );
+    }
+}
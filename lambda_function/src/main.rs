@@ -1,5 +1,7 @@
 use lambda_runtime::{run, service_fn, Error, LambdaEvent};
 use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tracing::{field::Empty, Instrument, Span};
 
 /// This is a made-up example. Requests come into the runtime as unicode
 /// strings in json format, which can map to any structure that implements `serde::Deserialize`
@@ -18,18 +20,44 @@ struct Response {
     vector_sorted: String, // Changed field name to vector_sorted
 }
 
+fn millis(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+// Every invocation runs in a span with the Lambda request id and the time
+// spent parsing, sorting and serializing, logged as JSON on completion.
+async fn traced_handler(event: LambdaEvent<Request>) -> Result<Response, Error> {
+    let span = tracing::info_span!(
+        "request",
+        request_id = %event.context.request_id,
+        parse_ms = Empty,
+        sort_ms = Empty,
+        serialize_ms = Empty,
+    );
+    let start = Instant::now();
+    let result = function_handler(event).instrument(span.clone()).await;
+    span.in_scope(|| match &result {
+        Ok(_) => tracing::info!(latency_ms = millis(start), "request"),
+        Err(err) => tracing::error!(latency_ms = millis(start), error = %err, "request"),
+    });
+    result
+}
+
 async fn function_handler(event: LambdaEvent<Request>) -> Result<Response, Error> {
     // Extract the vector from the request
     let vector_str = &event.payload.vector;
 
     // Parse the string representation of the vector into a Vec<i32>
+    let start = Instant::now();
     let vector: Vec<i32> = serde_json::from_str(vector_str)
         .map_err(|e| {
-            eprintln!("Error parsing vector: {}", e);
+            tracing::error!(error = %e, "Error parsing vector");
             Error::from("Error parsing vector")
         })?;
+    Span::current().record("parse_ms", millis(start));
 
     // Sort the vector using insertion sort
+    let start = Instant::now();
     let mut sorted_vector = vector.clone();
     let length = sorted_vector.len();
 
@@ -43,40 +71,41 @@ async fn function_handler(event: LambdaEvent<Request>) -> Result<Response, Error
         sorted_vector[(i + 1) as usize] = key;
     }
 
+    Span::current().record("sort_ms", millis(start));
+
     // Serialize the sorted vector back into a string
+    let start = Instant::now();
     let sorted_vector_str = serde_json::to_string(&sorted_vector)
         .map_err(|e| {
-            eprintln!("Error serializing sorted vector: {}", e);
+            tracing::error!(error = %e, "Error serializing sorted vector");
             Error::from("Error serializing sorted vector")
         })?;
 
-    // Prepare the response
-    // let resp = Response {
-    //     vector_sorted: sorted_vector_str,
-    // };
-
-    let message = format!("sorted_vector:{sorted_vector_str}");
+    Span::current().record("serialize_ms", millis(start));
 
-    let resp = Response::builder()
-    .status(200)
-    .header("content-type", "text/html")
-    .body(message.into())
-    .map_err(Box::new)?;
-    Ok(resp);
+    // Prepare the response
+    let resp = Response {
+        vector_sorted: sorted_vector_str,
+    };
 
     // Return the response
-    // Ok(resp)
+    Ok(resp)
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     tracing_subscriber::fmt()
+        .json()
         .with_max_level(tracing::Level::INFO)
         // disable printing the name of the module in every log line.
         .with_target(false)
         // disabling time is handy because CloudWatch will add the ingestion time.
         .without_time()
+        // event fields at the top level, the request span's under `span`.
+        .flatten_event(true)
+        .with_current_span(true)
+        .with_span_list(false)
         .init();
 
-    run(service_fn(function_handler)).await
+    run(service_fn(traced_handler)).await
 }